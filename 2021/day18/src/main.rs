@@ -2,51 +2,53 @@ mod node;
 
 use std::fs;
 
-use itertools::Itertools;
 use node::Node;
+use rayon::prelude::*;
 
-// TODO: use 'slotmap' for node tree data?
 fn main() {
     let input = fs::read_to_string("./_data/input.txt").expect("oh noes");
 
     let output = process_data(input.clone());
-    let adv_output = process_data_adv(input);
+    let adv_output = process_data_adv(input.clone());
 
     println!("Result is: {}", output);
     println!("Adv result is: {}", adv_output);
 }
 
 fn process_data(input: String) -> String {
-    let mut node: Option<Node> = None;
-
-    for line in input.trim().lines().map(|l| l.trim()) {
-        if let Some(n) = node {
-            node = Some(n.add(Node::from(line)));
-        } else {
-            node = Some(Node::from(line));
-        }
-    }
-
-    node.unwrap().magnitude().to_string()
+    parse(&input)
+        .into_iter()
+        .sum::<Node>()
+        .magnitude()
+        .to_string()
 }
 
 fn process_data_adv(input: String) -> String {
-    input
-        .trim()
-        .lines()
-        .map(|l| l.trim())
-        .permutations(2)
-        .filter_map(|a| {
-            a.get(0)
-                .and_then(|s1| a.get(1).map(|s2| (s1.to_owned(), s2.to_owned())))
+    let nodes = parse(&input);
+
+    nodes
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(i, a)| {
+            nodes
+                .iter()
+                .enumerate()
+                .filter(move |(j, _)| i != *j)
+                .map(move |(_, b)| (a.clone() + b.clone()).magnitude())
         })
-        .map(|(s1, s2)| Node::from(s1).add(Node::from(s2)))
-        .map(|n| n.magnitude())
         .max()
         .unwrap()
         .to_string()
 }
 
+fn parse(input: &str) -> Vec<Node> {
+    input
+        .trim()
+        .lines()
+        .map(|l| l.trim().parse::<Node>().expect("invalid snailfish number"))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::*;
@@ -1,88 +1,81 @@
-﻿use nom::{
-    branch::alt,
-    character::complete::{char, digit0},
-    combinator::{map, map_opt},
-    sequence::{delimited, separated_pair},
-    IResult,
+use std::{
+    fmt::{self, Display},
+    iter::Sum,
+    ops::Add,
+    str::FromStr,
 };
 
-#[derive(Debug)]
-enum NodeValue {
+#[derive(Debug, Clone, Copy)]
+enum Slot {
     Literal(u32),
-    Child(Box<Node>),
+    Pair(usize, usize),
+    Free,
 }
 
-#[derive(Clone, Copy, std::cmp::PartialEq)]
-enum NodeCommand {
-    AddLeft(u32),
-    AddRight(u32),
-    Done,
-    None,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Explode,
+    Split,
 }
 
-#[derive(Debug)]
-pub struct Node {
-    left: NodeValue,
-    right: NodeValue,
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub action: Action,
+    pub state: String,
 }
 
-impl ToString for Node {
-    fn to_string(&self) -> String {
-        let left = match &self.left {
-            NodeValue::Literal(v) => v.to_string(),
-            NodeValue::Child(bn) => bn.to_string(),
-        };
-        let right = match &self.right {
-            NodeValue::Literal(v) => v.to_string(),
-            NodeValue::Child(bn) => bn.to_string(),
-        };
-
-        format!("[{},{}]", left, right)
-    }
+/// Snailfish number stored as a flat arena of slots,
+/// pairs reference their children by index.
+#[derive(Debug, Clone)]
+pub struct Node {
+    arena: Vec<Slot>,
+    free: Vec<usize>,
+    root: usize,
 }
 
 impl Node {
-    pub fn from(s: &str) -> Self {
-        let (rem, node) = parser_tuple(s).unwrap();
-
-        if !rem.is_empty() {
-            panic!("Unparsed data: {}", rem);
-        }
+    pub fn magnitude(&self) -> u32 {
+        self.magnitude_of(self.root)
+    }
 
-        node
+    pub fn reduce(&mut self) {
+        self.reduce_with(|_, _| {});
     }
 
-    pub fn add(self, value: Node) -> Node {
-        let mut res = Node {
-            left: NodeValue::Child(self.into()),
-            right: NodeValue::Child(value.into()),
-        };
+    /// Same as `reduce`, but records the number after every explode or split.
+    #[cfg(test)]
+    pub fn reduce_traced(&mut self) -> Vec<Step> {
+        let mut trace = vec![];
 
-        res.normalize();
+        self.reduce_with(|action, node| {
+            trace.push(Step {
+                action,
+                state: node.to_string(),
+            })
+        });
 
-        res
+        trace
     }
 
-    pub fn magnitude(&self) -> u32 {
-        let left = match &self.left {
-            NodeValue::Literal(v) => *v,
-            NodeValue::Child(n) => n.magnitude(),
-        };
-        let right = match &self.right {
-            NodeValue::Literal(v) => *v,
-            NodeValue::Child(n) => n.magnitude(),
-        };
+    /// Adds two numbers and returns the sum along with its reduction trace.
+    #[cfg(test)]
+    pub fn add_traced(self, rhs: Node) -> (Node, Vec<Step>) {
+        let mut res = self.join(rhs);
+        let trace = res.reduce_traced();
 
-        (left * 3) + (right * 2)
+        (res, trace)
     }
 
-    fn normalize(&mut self) {
+    fn reduce_with(&mut self, mut on_step: impl FnMut(Action, &Node)) {
         loop {
             if self.explode() {
+                on_step(Action::Explode, self);
                 continue;
             }
 
             if self.split() {
+                on_step(Action::Split, self);
                 continue;
             }
 
@@ -90,153 +83,231 @@ impl Node {
         }
     }
 
-    fn explode(&mut self) -> bool {
-        explode(self, 0) != NodeCommand::None
+    fn join(mut self, rhs: Node) -> Node {
+        let offset = self.arena.len();
+
+        self.arena
+            .extend(rhs.arena.into_iter().map(|slot| match slot {
+                Slot::Pair(l, r) => Slot::Pair(l + offset, r + offset),
+                s => s,
+            }));
+        self.free.extend(rhs.free.into_iter().map(|i| i + offset));
+
+        self.root = self.alloc(Slot::Pair(self.root, rhs.root + offset));
+        self
     }
 
-    fn split(&mut self) -> bool {
-        if match &mut self.left {
-            NodeValue::Literal(v) => {
-                if *v > 9 {
-                    self.left = NodeValue::Child(Node::from_split(*v).into());
-                    true
-                } else {
-                    false
+    fn explode(&mut self) -> bool {
+        let mut leaves = vec![];
+        let mut target = None;
+        let mut stack = vec![(self.root, 0)];
+
+        while let Some((idx, depth)) = stack.pop() {
+            match self.arena[idx] {
+                Slot::Literal(_) => leaves.push(idx),
+                Slot::Pair(l, r) => {
+                    if target.is_none()
+                        && depth >= 4
+                        && matches!(
+                            (self.arena[l], self.arena[r]),
+                            (Slot::Literal(_), Slot::Literal(_))
+                        )
+                    {
+                        target = Some((idx, leaves.len()));
+                    }
+
+                    stack.push((r, depth + 1));
+                    stack.push((l, depth + 1));
                 }
+                Slot::Free => panic!("Free slot reachable from root."),
             }
-            NodeValue::Child(n) => n.split(),
-        } {
-            return true;
         }
 
-        match &mut self.right {
-            NodeValue::Literal(v) => {
-                if *v > 9 {
-                    self.right = NodeValue::Child(Node::from_split(*v).into());
-                    true
-                } else {
-                    false
-                }
-            }
-            NodeValue::Child(n) => n.split(),
+        let Some((idx, pos)) = target else {
+            return false;
+        };
+
+        let Slot::Pair(l, r) = self.arena[idx] else {
+            unreachable!()
+        };
+        let (Slot::Literal(lv), Slot::Literal(rv)) = (self.arena[l], self.arena[r]) else {
+            unreachable!()
+        };
+
+        if let Some(&prev) = pos.checked_sub(1).and_then(|p| leaves.get(p)) {
+            self.incr(prev, lv);
         }
-    }
 
-    fn from_split(v: u32) -> Self {
-        Node {
-            left: NodeValue::Literal(v / 2),
-            right: NodeValue::Literal(v / 2 + (v % 2)),
+        if let Some(&next) = leaves.get(pos + 2) {
+            self.incr(next, rv);
         }
-    }
-}
 
-fn explode(node: &mut Node, layer: u32) -> NodeCommand {
-    if let NodeValue::Child(n) = &mut node.left {
-        if layer == 3 {
-            let left = match n.left {
-                NodeValue::Literal(v) => v,
-                NodeValue::Child(_) => panic!("Wrong structure, too deep."),
-            };
-
-            let right = match n.right {
-                NodeValue::Literal(v) => v,
-                NodeValue::Child(_) => panic!("Wrong structure, too deep."),
-            };
-
-            match &mut node.right {
-                NodeValue::Literal(v) => node.right = NodeValue::Literal(right + *v),
-                NodeValue::Child(n) => incr_first_left(n, right),
-            }
+        self.release(l);
+        self.release(r);
+        self.arena[idx] = Slot::Literal(0);
 
-            node.left = NodeValue::Literal(0);
+        true
+    }
 
-            return NodeCommand::AddLeft(left);
-        } else {
-            let cmd = explode(n, layer + 1);
+    fn split(&mut self) -> bool {
+        let mut stack = vec![self.root];
 
-            if let NodeCommand::AddRight(v) = cmd {
-                match &mut node.right {
-                    NodeValue::Literal(e) => node.right = NodeValue::Literal(v + *e),
-                    NodeValue::Child(n) => incr_first_left(n, v),
-                }
+        while let Some(idx) = stack.pop() {
+            match self.arena[idx] {
+                Slot::Literal(v) if v > 9 => {
+                    let l = self.alloc(Slot::Literal(v / 2));
+                    let r = self.alloc(Slot::Literal(v / 2 + (v % 2)));
+                    self.arena[idx] = Slot::Pair(l, r);
 
-                return NodeCommand::Done;
-            } else if cmd != NodeCommand::None {
-                return cmd;
+                    return true;
+                }
+                Slot::Literal(_) => {}
+                Slot::Pair(l, r) => {
+                    stack.push(r);
+                    stack.push(l);
+                }
+                Slot::Free => panic!("Free slot reachable from root."),
             }
         }
-    };
-
-    if let NodeValue::Child(n) = &mut node.right {
-        if layer == 3 {
-            let left = match n.left {
-                NodeValue::Literal(v) => v,
-                NodeValue::Child(_) => panic!("Wrong structure, too deep."),
-            };
-
-            match &mut node.left {
-                NodeValue::Literal(v) => node.left = NodeValue::Literal(left + *v),
-                NodeValue::Child(n) => incr_first_right(n, left),
-            }
 
-            let right = match n.right {
-                NodeValue::Literal(v) => v,
-                NodeValue::Child(_) => panic!("Wrong structure, too deep."),
-            };
+        false
+    }
 
-            node.right = NodeValue::Literal(0);
+    fn incr(&mut self, idx: usize, value: u32) {
+        if let Slot::Literal(v) = &mut self.arena[idx] {
+            *v += value;
+        }
+    }
 
-            return NodeCommand::AddRight(right);
+    fn alloc(&mut self, slot: Slot) -> usize {
+        if let Some(idx) = self.free.pop() {
+            self.arena[idx] = slot;
+            idx
         } else {
-            let cmd = explode(n, layer + 1);
+            self.arena.push(slot);
+            self.arena.len() - 1
+        }
+    }
 
-            if let NodeCommand::AddLeft(v) = cmd {
-                match &mut node.left {
-                    NodeValue::Literal(e) => node.left = NodeValue::Literal(v + *e),
-                    NodeValue::Child(n) => incr_first_right(n, v),
-                }
+    fn release(&mut self, idx: usize) {
+        self.arena[idx] = Slot::Free;
+        self.free.push(idx);
+    }
 
-                return NodeCommand::Done;
-            } else if cmd != NodeCommand::None {
-                return cmd;
+    fn magnitude_of(&self, idx: usize) -> u32 {
+        match self.arena[idx] {
+            Slot::Literal(v) => v,
+            Slot::Pair(l, r) => self.magnitude_of(l) * 3 + self.magnitude_of(r) * 2,
+            Slot::Free => panic!("Free slot reachable from root."),
+        }
+    }
+
+    fn fmt_slot(&self, idx: usize, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.arena[idx] {
+            Slot::Literal(v) => write!(f, "{}", v),
+            Slot::Pair(l, r) => {
+                write!(f, "[")?;
+                self.fmt_slot(l, f)?;
+                write!(f, ",")?;
+                self.fmt_slot(r, f)?;
+                write!(f, "]")
             }
+            Slot::Free => panic!("Free slot reachable from root."),
         }
-    };
+    }
+}
 
-    NodeCommand::None
+impl Add for Node {
+    type Output = Node;
+
+    fn add(self, rhs: Node) -> Node {
+        let mut res = self.join(rhs);
+        res.reduce();
+        res
+    }
 }
 
-fn incr_first_left(node: &mut Node, value: u32) {
-    match &mut node.left {
-        NodeValue::Literal(v) => node.left = NodeValue::Literal(value + *v),
-        NodeValue::Child(n) => incr_first_left(n, value),
+impl Sum for Node {
+    fn sum<I: Iterator<Item = Node>>(iter: I) -> Self {
+        iter.reduce(Add::add)
+            .expect("Cannot sum an empty list of numbers.")
     }
 }
 
-fn incr_first_right(node: &mut Node, value: u32) {
-    match &mut node.right {
-        NodeValue::Literal(v) => node.right = NodeValue::Literal(value + *v),
-        NodeValue::Child(n) => incr_first_right(n, value),
+impl Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_slot(self.root, f)
     }
 }
 
-fn parser_tuple(i: &str) -> IResult<&str, Node, ()> {
-    let mapped_node = map(
-        separated_pair(parser_value_or_tuple, char(','), parser_value_or_tuple),
-        |(l, r)| Node { left: l, right: r },
-    );
+impl FromStr for Node {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut node = Node {
+            arena: vec![],
+            free: vec![],
+            root: 0,
+        };
+
+        let bytes = s.as_bytes();
+        let mut pos = 0;
+
+        if bytes.first() != Some(&b'[') {
+            return Err(format!("Expected a pair: {}", s));
+        }
+
+        node.root = parse_value(&mut node, bytes, &mut pos)?;
+
+        if pos != bytes.len() {
+            return Err(format!("Unparsed data: {}", &s[pos..]));
+        }
 
-    delimited(char('['), mapped_node, char(']'))(i)
+        Ok(node)
+    }
 }
 
-fn parser_value_or_tuple(i: &str) -> IResult<&str, NodeValue, ()> {
-    let mapped_digit0 = map_opt(digit0, |s: &str| {
-        s.parse::<u32>().map(NodeValue::Literal).ok()
-    });
+fn parse_value(node: &mut Node, bytes: &[u8], pos: &mut usize) -> Result<usize, String> {
+    match bytes.get(*pos) {
+        Some(b'[') => {
+            *pos += 1;
+            let l = parse_value(node, bytes, pos)?;
+            expect_byte(bytes, pos, b',')?;
+            let r = parse_value(node, bytes, pos)?;
+            expect_byte(bytes, pos, b']')?;
+
+            Ok(node.alloc(Slot::Pair(l, r)))
+        }
+        Some(b) if b.is_ascii_digit() => {
+            let start = *pos;
+            while bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
+            }
+
+            let value = std::str::from_utf8(&bytes[start..*pos])
+                .map_err(|e| e.to_string())?
+                .parse::<u32>()
+                .map_err(|e| e.to_string())?;
 
-    let mapped_tuple = map(parser_tuple, |n| NodeValue::Child(Box::new(n)));
+            Ok(node.alloc(Slot::Literal(value)))
+        }
+        Some(&b) => Err(format!("Unexpected '{}' at {}", b as char, pos)),
+        None => Err("Unexpected end of input".to_string()),
+    }
+}
 
-    alt((mapped_digit0, mapped_tuple))(i)
+fn expect_byte(bytes: &[u8], pos: &mut usize, expected: u8) -> Result<(), String> {
+    match bytes.get(*pos) {
+        Some(&b) if b == expected => {
+            *pos += 1;
+            Ok(())
+        }
+        Some(&b) => Err(format!(
+            "Expected '{}' at {}, got '{}'",
+            expected as char, pos, b as char
+        )),
+        None => Err(format!("Expected '{}', got end of input", expected as char)),
+    }
 }
 
 #[cfg(test)]
@@ -252,7 +323,18 @@ mod tests {
     #[case("[[[[5,0],[7,4]],[5,5]],[6,6]]")]
     #[case("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]")]
     fn parse_roundtrip(#[case] raw: &str) {
-        assert_eq!(raw, Node::from(raw).to_string());
+        assert_eq!(raw, raw.parse::<Node>().unwrap().to_string());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("5")]
+    #[case("[1,2")]
+    #[case("[1;2]")]
+    #[case("[1,2]]")]
+    #[case("[[1,a],2]")]
+    fn parse_errors(#[case] raw: &str) {
+        assert!(raw.parse::<Node>().is_err());
     }
 
     #[rstest]
@@ -264,7 +346,7 @@ mod tests {
     #[case("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]", 3488)]
     #[case("[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]", 4140)]
     fn magnitude_check(#[case] raw: &str, #[case] mag: u32) {
-        assert_eq!(mag, Node::from(raw).magnitude());
+        assert_eq!(mag, raw.parse::<Node>().unwrap().magnitude());
     }
 
     #[rstest]
@@ -276,12 +358,35 @@ mod tests {
         "[[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]],[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]]",
         "[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]"
     )]
-    fn normalize_check(#[case] raw: &str, #[case] exp: &str) {
-        let mut node = Node::from(raw);
-        node.normalize();
+    fn reduce_check(#[case] raw: &str, #[case] exp: &str) {
+        let mut node = raw.parse::<Node>().unwrap();
+        node.reduce();
         assert_eq!(exp, node.to_string());
     }
 
+    #[test]
+    fn trace_check() {
+        let lhs = "[[[[4,3],4],4],[7,[[8,4],9]]]".parse::<Node>().unwrap();
+        let rhs = "[1,1]".parse::<Node>().unwrap();
+
+        let (node, trace) = lhs.add_traced(rhs);
+
+        let exp = [
+            (Action::Explode, "[[[[0,7],4],[7,[[8,4],9]]],[1,1]]"),
+            (Action::Explode, "[[[[0,7],4],[15,[0,13]]],[1,1]]"),
+            (Action::Split, "[[[[0,7],4],[[7,8],[0,13]]],[1,1]]"),
+            (Action::Split, "[[[[0,7],4],[[7,8],[0,[6,7]]]],[1,1]]"),
+            (Action::Explode, "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"),
+        ]
+        .map(|(action, state)| Step {
+            action,
+            state: state.to_string(),
+        });
+
+        assert_eq!(exp.to_vec(), trace);
+        assert_eq!("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]", node.to_string());
+    }
+
     #[rstest]
     #[case(
         "[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]
@@ -310,16 +415,12 @@ mod tests {
         "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]"
     )]
     fn sum_check(#[case] inputs: &str, #[case] exp: &str) {
-        let mut node: Option<Node> = None;
+        let node: Node = inputs
+            .trim()
+            .lines()
+            .map(|l| l.trim().parse::<Node>().unwrap())
+            .sum();
 
-        for line in inputs.trim().lines().map(|l| l.trim()) {
-            if let Some(n) = node {
-                node = Some(n.add(Node::from(line)));
-            } else {
-                node = Some(Node::from(line));
-            }
-        }
-
-        assert_eq!(exp, node.unwrap().to_string());
+        assert_eq!(exp, node.to_string());
     }
 }
@@ -1,75 +1,66 @@
-use std::{collections::BTreeSet, fs};
+mod reactor;
+
+use std::fs;
 
 use lazy_static::lazy_static;
 use regex::{Match, Regex};
 
+use reactor::{Cuboid, Reactor};
+
 fn main() {
     let input = fs::read_to_string("./_data/input.txt").expect("oh noes");
 
     let output = process_data(input.clone());
-    let adv_output = process_data_adv(input);
+    let adv_output = process_data_adv(input.clone());
 
     println!("Result is: {}", output);
     println!("Adv result is: {}", adv_output);
-}
 
-fn process_data(input: String) -> String {
-    let commands = parse(input);
-
-    let mut on_set = BTreeSet::new();
-
-    for cmd in commands.into_iter() {
-        for x in cmd.x_range.0.max(-50)..=cmd.x_range.1.min(50) {
-            for y in cmd.y_range.0.max(-50)..=cmd.y_range.1.min(50) {
-                for z in cmd.z_range.0.max(-50)..=cmd.z_range.1.min(50) {
-                    if cmd.toggle {
-                        on_set.insert((x, y, z));
-                    } else {
-                        on_set.remove(&(x, y, z));
-                    }
-                }
-            }
-        }
+    if cfg!(feature = "report") {
+        report(input);
     }
-
-    on_set.len().to_string()
 }
 
-fn process_data_adv(input: String) -> String {
-    let commands = parse(input);
+/// Live cuboids after every step of the full reboot and whether the origin
+/// cube ends up lit, printed with the `report` feature.
+fn report(input: String) {
+    let (reactor, live) = reboot(input, None);
 
-    let mut pos_cubes: Vec<Cuboid> = Vec::new();
-    let mut neg_cubes: Vec<Cuboid> = Vec::new();
+    for (i, count) in live.iter().enumerate() {
+        println!("Step {}: {} live cuboids", i + 1, count);
+    }
 
-    for cmd in commands.into_iter() {
-        let add_self = cmd.toggle;
-        let cube = Cuboid::from_command(cmd);
+    let state = if reactor.contains(&[0, 0, 0]) {
+        "on"
+    } else {
+        "off"
+    };
+    println!("Origin cube is {}", state);
+}
 
-        let pos_inters: Vec<Cuboid> = pos_cubes
-            .iter()
-            .filter_map(|c| c.intersection(&cube))
-            .collect();
+fn process_data(input: String) -> String {
+    let clip = Cuboid::new([-50..51, -50..51, -50..51]);
 
-        let neg_inters: Vec<Cuboid> = neg_cubes
-            .iter()
-            .filter_map(|c| c.intersection(&cube))
-            .collect();
+    reboot(input, Some(clip)).0.volume().to_string()
+}
 
-        pos_cubes.extend(neg_inters);
-        neg_cubes.extend(pos_inters);
+fn process_data_adv(input: String) -> String {
+    reboot(input, None).0.volume().to_string()
+}
 
-        if add_self {
-            pos_cubes.push(cube);
-        }
-    }
+/// Reactor after every step, with the number of live cuboids after each of them.
+fn reboot(input: String, clip: Option<Cuboid>) -> (Reactor, Vec<usize>) {
+    let mut reactor = Reactor::new(clip);
 
-    let pos_sum: i64 = pos_cubes.iter().map(|c| c.size()).sum();
-    let neg_sum: i64 = neg_cubes.iter().map(|c| c.size()).sum();
+    let live = parse(input)
+        .iter()
+        .map(|step| reactor.apply(&step.cuboid, step.on))
+        .collect();
 
-    (pos_sum - neg_sum).to_string()
+    (reactor, live)
 }
 
-fn parse(input: String) -> Vec<Command> {
+fn parse(input: String) -> Vec<Step> {
     lazy_static! {
         static ref LINE_RE: Regex = Regex::new("(on|off) x=(-?)(\\d+)\\.\\.(-?)(\\d+),y=(-?)(\\d+)\\.\\.(-?)(\\d+),z=(-?)(\\d+)\\.\\.(-?)(\\d+)").unwrap();
     }
@@ -79,24 +70,14 @@ fn parse(input: String) -> Vec<Command> {
         .lines()
         .map(|l| {
             let caps = LINE_RE.captures(l).unwrap();
-            let x_r = (
-                parse_i64(caps.get(2), caps.get(3)),
-                parse_i64(caps.get(4), caps.get(5)),
-            );
-            let y_r = (
-                parse_i64(caps.get(6), caps.get(7)),
-                parse_i64(caps.get(8), caps.get(9)),
-            );
-            let z_r = (
-                parse_i64(caps.get(10), caps.get(11)),
-                parse_i64(caps.get(12), caps.get(13)),
-            );
-
-            Command {
-                x_range: x_r,
-                y_range: y_r,
-                z_range: z_r,
-                toggle: caps.get(1).unwrap().as_str() == "on",
+            let range = |sign_from, from, sign_to, to| {
+                parse_i64(caps.get(sign_from), caps.get(from))
+                    ..parse_i64(caps.get(sign_to), caps.get(to)) + 1
+            };
+
+            Step {
+                cuboid: Cuboid::new([range(2, 3, 4, 5), range(6, 7, 8, 9), range(10, 11, 12, 13)]),
+                on: caps.get(1).unwrap().as_str() == "on",
             }
         })
         .collect()
@@ -111,62 +92,9 @@ fn parse_i64(sign: Option<Match>, num: Option<Match>) -> i64 {
     }
 }
 
-struct Command {
-    x_range: (i64, i64),
-    y_range: (i64, i64),
-    z_range: (i64, i64),
-    toggle: bool,
-}
-
-struct Cuboid {
-    x_range: (i64, i64),
-    y_range: (i64, i64),
-    z_range: (i64, i64),
-}
-
-impl Cuboid {
-    fn from_command(cmd: Command) -> Self {
-        Cuboid {
-            x_range: cmd.x_range,
-            y_range: cmd.y_range,
-            z_range: cmd.z_range,
-        }
-    }
-
-    fn size(&self) -> i64 {
-        let x_size = self.x_range.1 - self.x_range.0 + 1;
-        let y_size = self.y_range.1 - self.y_range.0 + 1;
-        let z_size = self.z_range.1 - self.z_range.0 + 1;
-
-        x_size * y_size * z_size
-    }
-
-    fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
-        if self.x_range.0 > other.x_range.1
-            || self.x_range.1 < other.x_range.0
-            || self.y_range.0 > other.y_range.1
-            || self.y_range.1 < other.y_range.0
-            || self.z_range.0 > other.z_range.1
-            || self.z_range.1 < other.z_range.0
-        {
-            return None;
-        }
-
-        Some(Cuboid {
-            x_range: (
-                self.x_range.0.max(other.x_range.0),
-                self.x_range.1.min(other.x_range.1),
-            ),
-            y_range: (
-                self.y_range.0.max(other.y_range.0),
-                self.y_range.1.min(other.y_range.1),
-            ),
-            z_range: (
-                self.z_range.0.max(other.z_range.0),
-                self.z_range.1.min(other.z_range.1),
-            ),
-        })
-    }
+struct Step {
+    cuboid: Cuboid,
+    on: bool,
 }
 
 #[cfg(test)]
@@ -268,6 +196,39 @@ mod tests {
     off x=-93533..-4276,y=-16170..68771,z=-104985..-24507
     ";
 
+    const TEST_CASE_SMALL: &str = "on x=10..12,y=10..12,z=10..12
+    on x=11..13,y=11..13,z=11..13
+    off x=9..11,y=9..11,z=9..11
+    on x=10..10,y=10..10,z=10..10
+    ";
+
+    #[test]
+    fn reactor_check() {
+        let mut reactor = Reactor::new(None);
+        let mut volumes = vec![];
+
+        for step in parse(TEST_CASE_SMALL.to_string()) {
+            reactor.apply(&step.cuboid, step.on);
+            volumes.push(reactor.volume());
+        }
+
+        assert_eq!(vec![27, 46, 38, 39], volumes);
+        assert!(reactor.contains(&[10, 10, 10]));
+        assert!(!reactor.contains(&[11, 11, 11]));
+        assert!(reactor.contains(&[13, 13, 13]));
+        assert!(!reactor.contains(&[14, 13, 13]));
+    }
+
+    #[test]
+    fn clip_check() {
+        let clip = Cuboid::new([10..12, 10..12, 10..14]);
+        let (reactor, live) = reboot(TEST_CASE_SMALL.to_string(), Some(clip));
+
+        assert_eq!(6, reactor.volume());
+        assert_eq!(vec![1, 4, 3, 4], live);
+        assert!(!reactor.contains(&[13, 13, 13]));
+    }
+
     #[test]
     fn adv_check() {
         assert_eq!(
@@ -0,0 +1,55 @@
+use rangeset::RangeBox;
+
+/// Half-open cuboid of cubes, `x`, `y` and `z` ranges in that order.
+pub type Cuboid = RangeBox<i64, 3>;
+
+/// Lit cubes kept as pairwise disjoint cuboids, every step is clipped to
+/// the optional region before it touches them.
+#[derive(Debug, Clone, Default)]
+pub struct Reactor {
+    clip: Option<Cuboid>,
+    cuboids: Vec<Cuboid>,
+}
+
+impl Reactor {
+    pub fn new(clip: Option<Cuboid>) -> Self {
+        Reactor {
+            clip,
+            cuboids: vec![],
+        }
+    }
+
+    /// Turns every cube of `cuboid` on or off, returns the number of live cuboids afterwards.
+    pub fn apply(&mut self, cuboid: &Cuboid, on: bool) -> usize {
+        let cuboid = match &self.clip {
+            Some(clip) => clip.intersection(cuboid),
+            None => Some(cuboid.clone()),
+        };
+
+        if let Some(cuboid) = cuboid {
+            self.cuboids = self
+                .cuboids
+                .iter()
+                .flat_map(|c| c.difference(&cuboid))
+                .collect();
+
+            if on {
+                self.cuboids.push(cuboid);
+            }
+        }
+
+        self.live()
+    }
+
+    pub fn live(&self) -> usize {
+        self.cuboids.len()
+    }
+
+    pub fn volume(&self) -> i64 {
+        self.cuboids.iter().map(|c| c.volume()).sum()
+    }
+
+    pub fn contains(&self, point: &[i64; 3]) -> bool {
+        self.cuboids.iter().any(|c| c.contains(point))
+    }
+}
@@ -1,6 +1,7 @@
 use std::fs;
 
 use itertools::Itertools;
+use rangeset::RangeSet;
 
 fn main() {
     let input = fs::read_to_string("./_data/input.txt").expect("oh noes");
@@ -13,48 +14,32 @@ fn main() {
 }
 
 fn process_data(input: String) -> String {
-    input
-        .lines()
-        .filter_map(|line| line.split(',').map(Range::from).next_tuple())
-        .filter(|(left, right)| left.is_full_overlap(right))
+    parse(&input)
+        .filter(|(left, right)| left.is_superset(right) || right.is_superset(left))
         .count()
         .to_string()
 }
 
 fn process_data_adv(input: String) -> String {
-    input
-        .lines()
-        .filter_map(|line| line.split(',').map(Range::from).next_tuple())
-        .filter(|(left, right)| left.is_partial_overlap(right))
+    parse(&input)
+        .filter(|(left, right)| left.intersects(right))
         .count()
         .to_string()
 }
 
-struct Range {
-    from: u32,
-    to: u32,
-}
-
-impl Range {
-    fn is_full_overlap(self: &Range, other: &Range) -> bool {
-        (self.from <= other.from && self.to >= other.to)
-            || (self.from >= other.from && self.to <= other.to)
-    }
-
-    fn is_partial_overlap(self: &Range, other: &Range) -> bool {
-        self.from <= other.to && self.to >= other.from
-    }
+fn parse(input: &str) -> impl Iterator<Item = (RangeSet<u32>, RangeSet<u32>)> + '_ {
+    input
+        .lines()
+        .filter_map(|line| line.split(',').map(parse_range).next_tuple())
 }
 
-impl From<&str> for Range {
-    fn from(value: &str) -> Self {
-        value
-            .split('-')
-            .filter_map(|val| val.parse::<u32>().ok())
-            .next_tuple()
-            .map(|(from, to)| Range { from, to })
-            .unwrap()
-    }
+fn parse_range(value: &str) -> RangeSet<u32> {
+    value
+        .split('-')
+        .filter_map(|val| val.parse::<u32>().ok())
+        .next_tuple()
+        .map(|(from, to)| RangeSet::try_from(from..=to).unwrap())
+        .unwrap()
 }
 
 #[cfg(test)]
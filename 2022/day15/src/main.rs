@@ -1,7 +1,8 @@
-use std::{fs, iter::once};
+use std::fs;
 
 use geo::{coord, polygon, BooleanOps, Centroid, MultiPolygon, Rect};
 use itertools::Itertools;
+use rangeset::RangeSet;
 use regex::Regex;
 
 fn main() {
@@ -15,8 +16,8 @@ fn main() {
 }
 
 fn process_data(input: String, line: isize) -> String {
-    let mut line_beacons = Vec::<isize>::new();
-    let mut line_ranges = Vec::<(isize, isize)>::new();
+    let mut covered = RangeSet::new();
+    let mut beacons = RangeSet::new();
 
     for (sensor, beacon) in input.lines().map(parse_line) {
         let range = get_range(&sensor, &beacon);
@@ -24,45 +25,15 @@ fn process_data(input: String, line: isize) -> String {
 
         if line_dist <= range {
             let sideways = range - line_dist;
-            line_ranges.push((sensor.x - sideways, sensor.x + sideways));
+            covered.insert(sensor.x - sideways..sensor.x + sideways + 1);
         }
 
         if beacon.y == line {
-            line_beacons.push(beacon.x);
+            beacons.insert(beacon.x..beacon.x + 1);
         }
     }
 
-    line_beacons.dedup();
-
-    line_ranges
-        .iter()
-        .sorted()
-        .fold(Vec::<(isize, isize)>::new(), |mut acc, &r| {
-            if let Some((last, elements)) = acc.split_last() {
-                if last.1 < r.0 {
-                    acc.push(r);
-                    acc
-                } else {
-                    elements
-                        .iter()
-                        .cloned()
-                        .chain(once((last.0, last.1.max(r.1))))
-                        .collect_vec()
-                }
-            } else {
-                vec![r]
-            }
-        })
-        .iter()
-        .map(|(x_s, x_e)| {
-            x_e - x_s + 1
-                - line_beacons
-                    .iter()
-                    .filter(|b_x| (x_s..=x_e).contains(b_x))
-                    .count() as isize
-        })
-        .sum::<isize>()
-        .to_string()
+    covered.difference(&beacons).len().to_string()
 }
 
 fn process_data_adv(input: String, max: usize) -> String {
@@ -0,0 +1,143 @@
+#[derive(Debug)]
+pub struct Valve {
+    pub name: String,
+    pub flow: u32,
+    pub tunnels: Vec<String>,
+}
+
+/// Valve graph reduced to the start and the valves worth opening,
+/// valve `i` of `flows` corresponds to bit `i` of a mask.
+#[derive(Debug)]
+pub struct Network {
+    flows: Vec<u32>,
+    /// `dists[i][j]` between useful valves, last row/column is the start.
+    dists: Vec<Vec<u32>>,
+}
+
+impl Network {
+    pub fn new(valves: &[Valve], start: &str) -> Self {
+        let all_dists = get_distances(valves);
+
+        let useful = valves
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.flow > 0)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let start = valves
+            .iter()
+            .position(|v| v.name == start)
+            .expect("unknown start valve");
+
+        let nodes = useful.iter().copied().chain([start]).collect::<Vec<_>>();
+
+        Network {
+            flows: useful.iter().map(|&i| valves[i].flow).collect(),
+            dists: nodes
+                .iter()
+                .map(|&i| nodes.iter().map(|&j| all_dists[i][j]).collect())
+                .collect(),
+        }
+    }
+
+    /// Most pressure released by `agents` actors working for `time` minutes each.
+    pub fn max_pressure(&self, agents: usize, time: u32) -> u32 {
+        let full = (1usize << self.flows.len()) - 1;
+
+        if agents == 0 {
+            return 0;
+        }
+
+        let single = self.best_per_mask(time);
+        let mut combined = single.clone();
+
+        for _ in 1..agents {
+            combined = (0..=full)
+                .map(|mask| {
+                    let mut best = single[mask];
+                    let mut sub = mask;
+
+                    while sub > 0 {
+                        best = best.max(combined[sub] + single[mask ^ sub]);
+                        sub = (sub - 1) & mask;
+                    }
+
+                    best
+                })
+                .collect();
+        }
+
+        combined[full]
+    }
+
+    /// Best pressure of a single actor opening only valves within each mask.
+    fn best_per_mask(&self, time: u32) -> Vec<u32> {
+        let mut best = vec![0; 1 << self.flows.len()];
+
+        self.visit(self.flows.len(), time, 0, 0, &mut best);
+
+        for bit in 0..self.flows.len() {
+            for mask in 0..best.len() {
+                if mask & (1 << bit) != 0 {
+                    best[mask] = best[mask].max(best[mask ^ (1 << bit)]);
+                }
+            }
+        }
+
+        best
+    }
+
+    fn visit(&self, current: usize, time_rem: u32, mask: usize, pressure: u32, best: &mut [u32]) {
+        best[mask] = best[mask].max(pressure);
+
+        for (next, flow) in self.flows.iter().enumerate() {
+            let dist = self.dists[current][next];
+
+            if mask & (1 << next) != 0 || dist + 1 >= time_rem {
+                continue;
+            }
+
+            let after_open = time_rem - dist - 1;
+
+            self.visit(
+                next,
+                after_open,
+                mask | (1 << next),
+                pressure + flow * after_open,
+                best,
+            );
+        }
+    }
+}
+
+/// Floyd–Warshall over the tunnel graph, unreachable pairs stay at `u32::MAX / 2`.
+fn get_distances(valves: &[Valve]) -> Vec<Vec<u32>> {
+    const UNREACHABLE: u32 = u32::MAX / 2;
+
+    let mut dists = vec![vec![UNREACHABLE; valves.len()]; valves.len()];
+
+    for (i, valve) in valves.iter().enumerate() {
+        dists[i][i] = 0;
+
+        for tun in valve.tunnels.iter() {
+            if let Some(j) = valves.iter().position(|v| &v.name == tun) {
+                dists[i][j] = 1;
+            }
+        }
+    }
+
+    for k in 0..valves.len() {
+        for i in 0..valves.len() {
+            for j in 0..valves.len() {
+                let through = dists[i][k] + dists[k][j];
+
+                if through < dists[i][j] {
+                    dists[i][j] = through;
+                }
+            }
+        }
+    }
+
+    dists
+}
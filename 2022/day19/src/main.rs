@@ -1,44 +1,51 @@
+mod optimizer;
+
 use std::fs;
 
 use indicatif::ProgressIterator;
 use itertools::Itertools;
+use optimizer::{Blueprint, Plan};
 use regex::Regex;
 
 fn main() {
     let input = fs::read_to_string("./_data/input.txt").expect("oh noes");
 
     let output = process_data(input.clone());
-    let adv_output = process_data_adv(input);
+    let adv_output = process_data_adv(input.clone());
 
     println!("Result is: {}", output);
     println!("Adv result is: {}", adv_output);
+
+    if cfg!(feature = "report") {
+        report(input);
+    }
+}
+
+/// Build order of the best 24 minute plan of every blueprint, printed with
+/// the `report` feature.
+fn report(input: String) {
+    for blueprint in parse(input) {
+        let plan = best_plan(&blueprint, 24);
+        let order = plan
+            .builds
+            .iter()
+            .map(|b| format!("{}@{}", blueprint.resources[b.robot], b.minute))
+            .join(", ");
+
+        println!(
+            "Blueprint {}: {} geodes [{}]",
+            blueprint.id, plan.value, order
+        );
+    }
 }
 
 fn process_data(input: String) -> String {
     const TIME_LIMIT: u32 = 24;
 
-    let blueprints = parse(input);
-    let resources = Resources::new();
-    let robots = Robots::new();
-
-    blueprints
+    parse(input)
         .iter()
         .progress()
-        .map(|b| {
-            (
-                b.id,
-                strategy(
-                    b,
-                    resources.clone(),
-                    robots.clone(),
-                    TIME_LIMIT,
-                    true,
-                    true,
-                    true,
-                ),
-            )
-        })
-        .map(|(id, geodes)| id * geodes)
+        .map(|b| b.id * best_plan(b, TIME_LIMIT).value)
         .sum::<u32>()
         .to_string()
 }
@@ -46,246 +53,79 @@ fn process_data(input: String) -> String {
 fn process_data_adv(input: String) -> String {
     const TIME_LIMIT: u32 = 32;
 
-    let blueprints = parse(input);
-    let resources = Resources::new();
-    let robots = Robots::new();
-
-    blueprints
+    parse(input)
         .iter()
         .take(3)
         .progress()
-        .map(|b| {
-            strategy(
-                b,
-                resources.clone(),
-                robots.clone(),
-                TIME_LIMIT,
-                true,
-                true,
-                true,
-            )
-        })
+        .map(|b| best_plan(b, TIME_LIMIT).value)
         .product::<u32>()
         .to_string()
 }
 
-fn strategy(
-    blueprint: &Blueprint,
-    resources: Resources,
-    robots: Robots,
-    time: u32,
-    can_build_ore: bool,
-    can_build_clay: bool,
-    can_build_obs: bool,
-) -> u32 {
-    if time == 0 {
-        return resources.cracked_geodes;
-    }
-
-    let (geo_ore_cost, geo_obs_cost) = blueprint.geode_robot_cost;
-    let (obs_ore_cost, obs_clay_cost) = blueprint.obsidian_robot_cost;
-    let max_ore = blueprint
-        .clay_robot_cost
-        .max(obs_ore_cost)
-        .max(geo_ore_cost);
-
-    let can_build_ore =
-        can_build_ore && robots.ore_robots < max_ore && resources.ores >= blueprint.ore_robot_cost;
-    let can_build_clay = can_build_clay
-        && robots.clay_robots < obs_clay_cost
-        && resources.ores >= blueprint.clay_robot_cost;
-    let can_build_obs = can_build_obs
-        && robots.obsidian_robots < geo_obs_cost
-        && resources.ores >= obs_ore_cost
-        && resources.clay >= obs_clay_cost;
-    let can_build_geo = resources.ores >= geo_ore_cost && resources.obsidian >= geo_obs_cost;
-
-    let mut branches = Vec::<u32>::new();
-
-    if can_build_geo || can_build_obs {
-        if can_build_geo {
-            let mut new_res = resources.clone();
-            let mut new_bot = robots.clone();
-            new_res.ores -= geo_ore_cost;
-            new_res.obsidian -= geo_obs_cost;
-            new_res.inc(&robots);
-            new_bot.geode_robots += 1;
-
-            branches.push(strategy(
-                blueprint,
-                new_res,
-                new_bot,
-                time - 1,
-                true,
-                true,
-                true,
-            ));
-        }
-
-        if can_build_obs {
-            let mut new_res = resources.clone();
-            let mut new_bot = robots.clone();
-            new_res.ores -= obs_ore_cost;
-            new_res.clay -= obs_clay_cost;
-            new_res.inc(&robots);
-            new_bot.obsidian_robots += 1;
-
-            branches.push(strategy(
-                blueprint,
-                new_res,
-                new_bot,
-                time - 1,
-                true,
-                true,
-                true,
-            ));
-        }
-
-        if can_build_geo && can_build_obs {
-            return branches.iter().max().cloned().unwrap();
-        }
-
-    } else {
-        if can_build_clay {
-            let mut new_res = resources.clone();
-            let mut new_bot = robots.clone();
-            new_res.ores -= blueprint.clay_robot_cost;
-            new_res.inc(&robots);
-            new_bot.clay_robots += 1;
-
-            branches.push(strategy(
-                blueprint,
-                new_res,
-                new_bot,
-                time - 1,
-                true,
-                true,
-                true,
-            ));
-        }
-
-        if can_build_ore {
-            let mut new_res = resources.clone();
-            let mut new_bot = robots.clone();
-            new_res.ores -= blueprint.ore_robot_cost;
-            new_res.inc(&robots);
-            new_bot.ore_robots += 1;
-
-            branches.push(strategy(
-                blueprint,
-                new_res,
-                new_bot,
-                time - 1,
-                true,
-                true,
-                true,
-            ));
-        }
-    }
+fn best_plan(blueprint: &Blueprint, time: u32) -> Plan {
+    let ore = blueprint.resource("ore").expect("blueprint without ore");
+    let geode = blueprint
+        .resource("geode")
+        .expect("blueprint without geode");
 
-    {
-        let can_build_ore = resources.ores < blueprint.ore_robot_cost;
-        let can_build_clay = resources.ores < blueprint.clay_robot_cost;
-        let can_build_obs = resources.ores < obs_ore_cost || resources.clay < obs_clay_cost;
-
-        let mut new_res = resources;
-        new_res.inc(&robots);
-        branches.push(strategy(
-            blueprint,
-            new_res,
-            robots.clone(),
-            time - 1,
-            can_build_ore,
-            can_build_clay,
-            can_build_obs,
-        ));
-    }
+    let mut robots = vec![0; blueprint.resources.len()];
+    robots[ore] = 1;
 
-    branches.iter().max().cloned().unwrap()
+    blueprint.optimize(&robots, geode, time)
 }
 
 fn parse(input: String) -> Vec<Blueprint> {
-    const PARSE_RGX: &str = concat!(
-        "Blueprint (?P<id>\\d+):",
-        ".+costs (?P<orebot>\\d+) ore",
-        ".+costs (?P<claybot>\\d+) ore",
-        ".+costs (?P<obsbotore>\\d+) ore and (?P<obsbotclay>\\d+) clay",
-        ".+costs (?P<geobotore>\\d+) ore and (?P<geobotobs>\\d+) obsidian."
-    );
-
-    let rgx = Regex::new(PARSE_RGX).unwrap();
+    let id_rgx = Regex::new("Blueprint (?P<id>\\d+):").unwrap();
+    let robot_rgx = Regex::new("Each (?P<robot>\\w+) robot costs (?P<costs>[^.]+)\\.").unwrap();
+    let cost_rgx = Regex::new("(?P<amount>\\d+) (?P<res>\\w+)").unwrap();
 
     input
         .lines()
-        .filter_map(|line| rgx.captures(line))
-        .map(|caps| Blueprint {
-            id: caps["id"].parse::<u32>().unwrap(),
-            ore_robot_cost: caps["orebot"].parse::<u32>().unwrap(),
-            clay_robot_cost: caps["claybot"].parse::<u32>().unwrap(),
-            obsidian_robot_cost: (
-                caps["obsbotore"].parse::<u32>().unwrap(),
-                caps["obsbotclay"].parse::<u32>().unwrap(),
-            ),
-            geode_robot_cost: (
-                caps["geobotore"].parse::<u32>().unwrap(),
-                caps["geobotobs"].parse::<u32>().unwrap(),
-            ),
+        .filter_map(|line| id_rgx.captures(line).map(|caps| (caps, line)))
+        .map(|(caps, line)| {
+            let mut resources = Vec::<String>::new();
+            let mut recipes = Vec::<(usize, Vec<(usize, u32)>)>::new();
+
+            for robot_caps in robot_rgx.captures_iter(line) {
+                let robot = index_of(&robot_caps["robot"], &mut resources);
+                let costs = cost_rgx
+                    .captures_iter(&robot_caps["costs"])
+                    .map(|c| {
+                        (
+                            index_of(&c["res"], &mut resources),
+                            c["amount"].parse::<u32>().unwrap(),
+                        )
+                    })
+                    .collect_vec();
+
+                recipes.push((robot, costs));
+            }
+
+            if recipes.len() != resources.len() {
+                panic!("Every resource needs a robot recipe: {}", line);
+            }
+
+            let mut costs = vec![vec![0; resources.len()]; resources.len()];
+            for (robot, recipe) in recipes {
+                for (res, amount) in recipe {
+                    costs[robot][res] = amount;
+                }
+            }
+
+            Blueprint {
+                id: caps["id"].parse::<u32>().unwrap(),
+                resources,
+                costs,
+            }
         })
         .collect_vec()
 }
 
-#[derive(Clone, Debug)]
-struct Resources {
-    ores: u32,
-    clay: u32,
-    obsidian: u32,
-    cracked_geodes: u32,
-}
-
-impl Resources {
-    fn new() -> Self {
-        Resources {
-            ores: 0,
-            clay: 0,
-            obsidian: 0,
-            cracked_geodes: 0,
-        }
-    }
-
-    fn inc(&mut self, robots: &Robots) {
-        self.ores += robots.ore_robots;
-        self.clay += robots.clay_robots;
-        self.obsidian += robots.obsidian_robots;
-        self.cracked_geodes += robots.geode_robots;
-    }
-}
-
-#[derive(Clone, Debug)]
-struct Robots {
-    ore_robots: u32,
-    clay_robots: u32,
-    obsidian_robots: u32,
-    geode_robots: u32,
-}
-
-impl Robots {
-    fn new() -> Self {
-        Robots {
-            ore_robots: 1,
-            clay_robots: 0,
-            obsidian_robots: 0,
-            geode_robots: 0,
-        }
-    }
-}
-
-#[derive(Debug)]
-struct Blueprint {
-    id: u32,
-    ore_robot_cost: u32,
-    clay_robot_cost: u32,
-    obsidian_robot_cost: (u32, u32),
-    geode_robot_cost: (u32, u32),
+fn index_of(name: &str, resources: &mut Vec<String>) -> usize {
+    resources.iter().position(|r| r == name).unwrap_or_else(|| {
+        resources.push(name.to_owned());
+        resources.len() - 1
+    })
 }
 
 #[cfg(test)]
@@ -309,4 +149,76 @@ Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsid
     fn adv_check(#[case] input: &str, #[case] expected: &str) {
         assert_eq!(expected, process_data_adv(input.to_owned()));
     }
+
+    #[rstest]
+    #[case(TEST_CASE, 24, vec![9, 12])]
+    #[case(TEST_CASE, 32, vec![56, 62])]
+    fn plan_check(#[case] input: &str, #[case] time: u32, #[case] expected: Vec<u32>) {
+        for (blueprint, exp) in parse(input.to_owned()).iter().zip(expected) {
+            let plan = best_plan(blueprint, time);
+
+            assert_eq!(exp, plan.value);
+            assert_eq!(exp, replay(blueprint, &plan, time));
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "Blueprint 1: Each wood robot costs 1 wood. Each gold robot costs 3 wood.",
+        6,
+        2
+    )]
+    #[case(
+        "Blueprint 1: Each wood robot costs 1 wood. Each gold robot costs 3 wood.",
+        8,
+        7
+    )]
+    #[case(
+        "Blueprint 1: Each wood robot costs 2 wood. Each stone robot costs 3 wood. Each gold robot costs 2 wood and 4 stone.",
+        12,
+        4
+    )]
+    fn generic_check(#[case] input: &str, #[case] time: u32, #[case] expected: u32) {
+        let blueprint = &parse(input.to_owned())[0];
+        let gold = blueprint.resource("gold").unwrap();
+
+        let mut robots = vec![0; blueprint.resources.len()];
+        robots[0] = 1;
+
+        let plan = blueprint.optimize(&robots, gold, time);
+
+        assert_eq!(expected, plan.value);
+    }
+
+    fn replay(blueprint: &Blueprint, plan: &Plan, time: u32) -> u32 {
+        let geode = blueprint.resource("geode").unwrap();
+        let mut resources = vec![0; blueprint.resources.len()];
+        let mut robots = vec![0; blueprint.resources.len()];
+        robots[blueprint.resource("ore").unwrap()] = 1;
+
+        let mut builds = plan.builds.iter().peekable();
+
+        for minute in 1..=time {
+            let build = builds.next_if(|b| b.minute == minute).map(|b| b.robot);
+
+            if let Some(robot) = build {
+                for (res, cost) in resources.iter_mut().zip(&blueprint.costs[robot]) {
+                    assert!(*res >= *cost, "unaffordable build at minute {}", minute);
+                    *res -= cost;
+                }
+            }
+
+            for (res, bots) in resources.iter_mut().zip(&robots) {
+                *res += bots;
+            }
+
+            if let Some(robot) = build {
+                robots[robot] += 1;
+            }
+        }
+
+        assert!(builds.next().is_none());
+
+        resources[geode]
+    }
 }
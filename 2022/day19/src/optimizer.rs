@@ -0,0 +1,129 @@
+#[derive(Debug, Clone)]
+pub struct Blueprint {
+    pub id: u32,
+    pub resources: Vec<String>,
+    /// `costs[robot][resource]`, robot `i` produces resource `i`.
+    pub costs: Vec<Vec<u32>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Build {
+    pub minute: u32,
+    pub robot: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Plan {
+    pub value: u32,
+    pub builds: Vec<Build>,
+}
+
+impl Blueprint {
+    pub fn resource(&self, name: &str) -> Option<usize> {
+        self.resources.iter().position(|r| r == name)
+    }
+
+    /// Finds the build order that collects the most of `target` resource
+    /// within `time` minutes, starting with the given robot counts.
+    pub fn optimize(&self, robots: &[u32], target: usize, time: u32) -> Plan {
+        let max_useful = (0..self.resources.len())
+            .map(|res| self.costs.iter().map(|c| c[res]).max().unwrap_or(0))
+            .collect();
+
+        let mut search = Search {
+            costs: &self.costs,
+            target,
+            time_limit: time,
+            max_useful,
+            best: Plan::default(),
+            path: vec![],
+        };
+
+        search.run(time, vec![0; self.resources.len()], robots.to_vec());
+        search.best
+    }
+}
+
+struct Search<'a> {
+    costs: &'a [Vec<u32>],
+    target: usize,
+    time_limit: u32,
+    max_useful: Vec<u32>,
+    best: Plan,
+    path: Vec<Build>,
+}
+
+impl Search<'_> {
+    fn run(&mut self, time_left: u32, resources: Vec<u32>, robots: Vec<u32>) {
+        let idle = resources[self.target] + robots[self.target] * time_left;
+
+        if idle > self.best.value {
+            self.best = Plan {
+                value: idle,
+                builds: self.path.clone(),
+            };
+        }
+
+        // optimistic: a new target robot every remaining minute
+        let bound = idle + time_left * time_left.saturating_sub(1) / 2;
+        if bound <= self.best.value {
+            return;
+        }
+
+        let target = self.target;
+        let order = std::iter::once(target).chain((0..robots.len()).rev().filter(|&r| r != target));
+
+        for robot in order {
+            if robot != self.target
+                && robots[robot] * time_left + resources[robot]
+                    >= self.max_useful[robot] * time_left
+            {
+                continue;
+            }
+
+            let Some(wait) = self.wait_time(robot, &resources, &robots) else {
+                continue;
+            };
+
+            if wait + 1 >= time_left {
+                continue;
+            }
+
+            let elapsed = wait + 1;
+            let new_resources = resources
+                .iter()
+                .zip(&robots)
+                .zip(&self.costs[robot])
+                .map(|((res, bots), cost)| res + bots * elapsed - cost)
+                .collect();
+
+            let mut new_robots = robots.clone();
+            new_robots[robot] += 1;
+
+            self.path.push(Build {
+                minute: self.time_limit - time_left + elapsed,
+                robot,
+            });
+            self.run(time_left - elapsed, new_resources, new_robots);
+            self.path.pop();
+        }
+    }
+
+    /// Minutes to wait until the robot is affordable, `None` if it never will be.
+    fn wait_time(&self, robot: usize, resources: &[u32], robots: &[u32]) -> Option<u32> {
+        self.costs[robot]
+            .iter()
+            .zip(resources)
+            .zip(robots)
+            .map(|((&cost, &res), &bots)| {
+                if res >= cost {
+                    Some(0)
+                } else if bots == 0 {
+                    None
+                } else {
+                    Some((cost - res).div_ceil(bots))
+                }
+            })
+            .try_fold(0, |acc, wait| wait.map(|w| acc.max(w)))
+    }
+}
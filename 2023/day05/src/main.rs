@@ -1,8 +1,9 @@
 #![feature(test)]
 
-use std::{collections::HashSet, fs, ops::Range};
+use std::fs;
 
 use itertools::Itertools;
+use rangeset::{OffsetMap, RangeSet};
 
 fn main() {
     let input = fs::read_to_string("./day05/_data/input.txt").expect("oh noes");
@@ -12,94 +13,40 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    let seeds = input
-        .lines()
-        .find(|line| line.starts_with("seeds:"))
-        .and_then(|line| line.split(':').nth(1))
-        .map(|nums| {
-            nums.split_ascii_whitespace()
-                .filter_map(|n| n.parse::<isize>().ok())
-                .collect_vec()
-        })
-        .unwrap();
+    let maps = parse_maps(input);
 
-    parse_maps(input)
-        .iter()
-        .fold(seeds, |acc, map| {
-            acc.iter().map(|s| get_dest(*s, map)).collect_vec()
-        })
-        .iter()
+    parse_seeds(input)
+        .map(|seed| maps.iter().fold(seed, |acc, map| map.map(acc)))
         .min()
         .unwrap()
         .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let seeds = input
-        .lines()
-        .find(|line| line.starts_with("seeds:"))
-        .and_then(|line| line.split(':').nth(1))
-        .map(|nums| {
-            nums.split_ascii_whitespace()
-                .filter_map(|n| n.parse::<isize>().ok())
-                .tuples()
-                .map(|(start, len)| start..(start + len))
-                .collect_vec()
-        })
-        .unwrap();
+    let seeds = parse_seeds(input)
+        .tuples()
+        .map(|(start, len)| start..(start + len))
+        .collect::<RangeSet<_>>();
 
     parse_maps(input)
         .iter()
-        .fold(seeds, |acc, map| {
-            acc.iter()
-                .flat_map(|r| get_range_dest(r.clone(), map))
-                .collect_vec()
-        })
-        .iter()
-        .map(|r| r.start)
+        .fold(seeds, |acc, map| map.map_set(&acc))
         .min()
         .unwrap()
         .to_string()
 }
 
-fn get_dest(source: isize, map: &[(Range<isize>, isize)]) -> isize {
-    map.iter()
-        .find(|(r, _)| r.contains(&source))
-        .map(|(r, d)| source + d - r.start)
-        .unwrap_or(source)
-}
-
-fn get_range_dest(source: Range<isize>, map: &[(Range<isize>, isize)]) -> Vec<Range<isize>> {
-    let mut res = Vec::<Range<isize>>::new();
-    let mut ranges = HashSet::<Range<isize>>::new();
-    ranges.insert(source.clone());
-
-    for (r, d) in map {
-        let shift = d - r.start;
-
-        for range in ranges.clone() {
-            if r.end <= range.start || r.start >= range.end {
-                continue;
-            }
-
-            ranges.remove(&range);
-            res.push((r.start.max(range.start) + shift)..(r.end.min(range.end) + shift));
-
-            if r.end < range.end {
-                ranges.insert(r.end..range.end);
-            }
-
-            if r.start > range.start {
-                ranges.insert(range.start..r.start);
-            }
-        }
-    }
-
-    res.extend(ranges);
-    res
+fn parse_seeds(input: &str) -> impl Iterator<Item = isize> + '_ {
+    input
+        .lines()
+        .find(|line| line.starts_with("seeds:"))
+        .and_then(|line| line.split(':').nth(1))
+        .unwrap()
+        .split_ascii_whitespace()
+        .filter_map(|n| n.parse::<isize>().ok())
 }
 
-fn parse_maps(input: &str) -> Vec<Vec<(Range<isize>, isize)>> {
+fn parse_maps(input: &str) -> Vec<OffsetMap<isize>> {
     vec![
         parse_map(input, "seed-to-soil"),
         parse_map(input, "soil-to-fertilizer"),
@@ -111,8 +58,8 @@ fn parse_maps(input: &str) -> Vec<Vec<(Range<isize>, isize)>> {
     ]
 }
 
-fn parse_map(input: &str, cat: &str) -> Vec<(Range<isize>, isize)> {
-    input
+fn parse_map(input: &str, cat: &str) -> OffsetMap<isize> {
+    let entries = input
         .lines()
         .skip_while(|line| !line.starts_with(cat))
         .skip(1)
@@ -123,8 +70,9 @@ fn parse_map(input: &str, cat: &str) -> Vec<(Range<isize>, isize)> {
         })
         .take_while(|o| o.is_some())
         .flatten()
-        .map(|(dest, source, len)| (source..(source + len), dest))
-        .collect_vec()
+        .map(|(dest, source, len)| (source..(source + len), dest));
+
+    OffsetMap::new(entries).expect("overlapping map entries")
 }
 
 #[cfg(test)]
@@ -0,0 +1,115 @@
+use itertools::Itertools;
+
+#[derive(PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum HandType {
+    High,
+    Pair,
+    TwoPair,
+    Three,
+    Full,
+    Four,
+    Five,
+}
+
+/// How two hands of the same type are told apart.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TieBreak {
+    /// Cards compared in the order they were dealt, the Camel Cards way.
+    FirstDiff,
+    /// Cards of the biggest groups first, then the kickers from high to low,
+    /// wildcards counting as the card they stand in for.
+    Poker,
+}
+
+/// Ranking of hands for a game: the cards from weakest to strongest, the
+/// ones standing in for any other and how ties are broken.
+#[derive(Debug, Clone)]
+pub struct Rules {
+    order: Vec<char>,
+    wildcards: Vec<char>,
+    tie_break: TieBreak,
+}
+
+impl Rules {
+    pub fn new(order: &str, wildcards: &str, tie_break: TieBreak) -> Result<Self, String> {
+        let order = order.chars().collect_vec();
+        if let Some(card) = order.iter().duplicates().next() {
+            return Err(format!("Card listed twice: {:?}", card));
+        }
+
+        let wildcards = wildcards.chars().collect_vec();
+        if let Some(card) = wildcards.iter().find(|c| !order.contains(c)) {
+            return Err(format!("Wildcard not in the card order: {:?}", card));
+        }
+
+        Ok(Rules {
+            order,
+            wildcards,
+            tie_break,
+        })
+    }
+
+    /// Hand from its cards, failing on any card the order doesn't know.
+    pub fn hand(&self, cards: &str) -> Result<Hand, String> {
+        let cards = cards
+            .chars()
+            .map(|c| {
+                self.order
+                    .iter()
+                    .position(|o| *o == c)
+                    .map(|rank| (rank, self.wildcards.contains(&c)))
+                    .ok_or_else(|| format!("Unknown card: {:?}", c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if cards.is_empty() {
+            return Err("Empty hand".to_owned());
+        }
+
+        // groups of the same card as (count, rank), biggest and strongest first
+        let mut groups = cards
+            .iter()
+            .filter(|(_, wild)| !wild)
+            .map(|(rank, _)| *rank)
+            .counts()
+            .into_iter()
+            .map(|(rank, count)| (count, rank))
+            .sorted()
+            .rev()
+            .collect_vec();
+
+        let wild = cards.iter().filter(|(_, wild)| *wild).count();
+        match groups.first_mut() {
+            Some((count, _)) => *count += wild,
+            None => groups.push((wild, self.order.len() - 1)),
+        }
+
+        let htype = match (groups[0].0, groups.get(1).map(|g| g.0)) {
+            (5.., _) => HandType::Five,
+            (4, _) => HandType::Four,
+            (3, Some(2)) => HandType::Full,
+            (3, _) => HandType::Three,
+            (2, Some(2)) => HandType::TwoPair,
+            (2, _) => HandType::Pair,
+            _ => HandType::High,
+        };
+
+        let ranks = match self.tie_break {
+            TieBreak::FirstDiff => cards.iter().map(|(rank, _)| *rank).collect(),
+            TieBreak::Poker => groups
+                .iter()
+                .flat_map(|(count, rank)| std::iter::repeat_n(*rank, *count))
+                .collect(),
+        };
+
+        Ok(Hand { htype, ranks })
+    }
+}
+
+/// A hand ranked under some rules, ordered by type and then card ranks.
+#[derive(PartialOrd, Ord, PartialEq, Eq, Debug)]
+pub struct Hand {
+    pub htype: HandType,
+    /// Card positions in the rules' order, arranged for the tie break.
+    ranks: Vec<usize>,
+}
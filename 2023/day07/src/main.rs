@@ -1,65 +1,69 @@
 #![feature(test)]
 
-mod joker_hands;
-mod regular_hands;
+mod hands;
 
 use std::fs;
 
 use itertools::Itertools;
-use joker_hands::{JCard, JHand};
-use regular_hands::{Card, Hand};
+
+use crate::hands::{Rules, TieBreak};
 
 fn main() {
     let input = fs::read_to_string("./day07/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
+
+    if cfg!(feature = "report") {
+        let rules = Rules::new("J23456789TQKA", "J", TieBreak::Poker).unwrap();
+        println!(
+            "Poker rules result is: {}",
+            winnings(&input, &rules).unwrap()
+        );
+    }
 }
 
 fn process_part_1(input: &str) -> String {
-    input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .filter_map(|line| line.split_ascii_whitespace().next_tuple())
-        .map(|(h, b)| {
-            let cards: Vec<Card> = h.chars().map(|c| c.into()).collect_vec();
-            Hand {
-                htype: cards.clone().into(),
-                cards,
-                bid: b.parse::<usize>().unwrap(),
-            }
-        })
-        .sorted()
-        .enumerate()
-        .map(|(i, h)| (i + 1) * h.bid)
-        .sum::<usize>()
-        .to_string()
+    let rules = Rules::new("23456789TJQKA", "", TieBreak::FirstDiff).unwrap();
+    winnings(input, &rules).unwrap().to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    input
+    let rules = Rules::new("J23456789TQKA", "J", TieBreak::FirstDiff).unwrap();
+    winnings(input, &rules).unwrap().to_string()
+}
+
+/// Every bid times the rank of its hand, the weakest hand ranking 1.
+fn winnings(input: &str, rules: &Rules) -> Result<usize, String> {
+    let hands = input
         .lines()
         .filter(|l| !l.is_empty())
-        .filter_map(|line| line.split_ascii_whitespace().next_tuple())
-        .map(|(h, b)| {
-            let cards: Vec<JCard> = h.chars().map(|c| c.into()).collect_vec();
-            JHand {
-                htype: cards.clone().into(),
-                cards,
-                bid: b.parse::<usize>().unwrap(),
-            }
+        .map(|line| {
+            let (cards, bid) = line
+                .split_ascii_whitespace()
+                .collect_tuple()
+                .ok_or_else(|| format!("Bad line: {:?}", line))?;
+            let bid = bid
+                .parse::<usize>()
+                .map_err(|e| format!("Bad bid {:?}: {}", bid, e))?;
+
+            Ok((rules.hand(cards)?, bid))
         })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(hands
+        .into_iter()
         .sorted()
         .enumerate()
-        .map(|(i, h)| (i + 1) * h.bid)
-        .sum::<usize>()
-        .to_string()
+        .map(|(i, (_, bid))| (i + 1) * bid)
+        .sum())
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
 
+    use crate::hands::HandType;
     use crate::*;
     use rstest::rstest;
     use test::Bencher;
@@ -83,6 +87,87 @@ QQQJA 483";
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case("23456789TJQKA", "", "32T3K", HandType::Pair)]
+    #[case("23456789TJQKA", "", "KTJJT", HandType::TwoPair)]
+    #[case("23456789TJQKA", "", "2345A", HandType::High)]
+    #[case("J23456789TQKA", "J", "KTJJT", HandType::Four)]
+    #[case("J23456789TQKA", "J", "QQQJA", HandType::Four)]
+    #[case("J23456789TQKA", "J", "JJJJJ", HandType::Five)]
+    #[case("J23456789TQKA", "J", "2233J", HandType::Full)]
+    #[case("J23456789TQKA", "J", "2345J", HandType::Pair)]
+    fn hand_type_check(
+        #[case] order: &str,
+        #[case] wildcards: &str,
+        #[case] cards: &str,
+        #[case] expected: HandType,
+    ) {
+        let rules = Rules::new(order, wildcards, TieBreak::FirstDiff).unwrap();
+        assert_eq!(expected, rules.hand(cards).unwrap().htype);
+    }
+
+    #[rstest]
+    // the pair decides before the first card does
+    #[case("A2Q2K", "33452", TieBreak::FirstDiff, true)]
+    #[case("A2Q2K", "33452", TieBreak::Poker, false)]
+    // same pair, the kickers from high to low
+    #[case("2A344", "Q4K43", TieBreak::FirstDiff, false)]
+    #[case("2A344", "Q4K43", TieBreak::Poker, true)]
+    // the three of a full house counts before its pair
+    #[case("22AAA", "333KK", TieBreak::Poker, true)]
+    #[case("222AA", "333KK", TieBreak::Poker, false)]
+    fn tie_break_check(
+        #[case] a: &str,
+        #[case] b: &str,
+        #[case] tie_break: TieBreak,
+        #[case] a_wins: bool,
+    ) {
+        let rules = Rules::new("23456789TJQKA", "", tie_break).unwrap();
+        assert_eq!(a_wins, rules.hand(a).unwrap() > rules.hand(b).unwrap());
+    }
+
+    #[test]
+    fn wild_poker_check() {
+        // the wildcard joins the strongest pair, so both hands are kings full of queens
+        let rules = Rules::new("J23456789TQKA", "J", TieBreak::Poker).unwrap();
+        let kings = rules.hand("KKQQJ").unwrap();
+        let queens = rules.hand("QQKKJ").unwrap();
+
+        assert_eq!(HandType::Full, kings.htype);
+        assert_eq!(kings, queens);
+        assert!(rules.hand("KKKJ2").unwrap() > rules.hand("QQQQA").unwrap());
+
+        // fours ranked by the card they are made of, not by the first one dealt
+        assert_eq!(6168, winnings(TEST_CASE, &rules).unwrap());
+    }
+
+    #[rstest]
+    #[case("23456789TJQKA", "", "32T3X 765", "Unknown card: 'X'")]
+    #[case("23456789TJQKA", "", "32T3K", "Bad line: \"32T3K\"")]
+    #[case(
+        "23456789TJQKA",
+        "",
+        "32T3K x",
+        "Bad bid \"x\": invalid digit found in string"
+    )]
+    #[case(
+        "23456789TJQKA",
+        "X",
+        "32T3K 765",
+        "Wildcard not in the card order: 'X'"
+    )]
+    #[case("23456789TJQKAA", "", "32T3K 765", "Card listed twice: 'A'")]
+    fn parse_error_check(
+        #[case] order: &str,
+        #[case] wildcards: &str,
+        #[case] input: &str,
+        #[case] expected: &str,
+    ) {
+        let res = Rules::new(order, wildcards, TieBreak::FirstDiff)
+            .and_then(|rules| winnings(input, &rules));
+        assert_eq!(Err(expected.to_owned()), res);
+    }
+
     #[rstest]
     #[case("249204891")]
     fn part_1_control(#[case] expected: &str) {
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use super::number_theory::{crt, Congruence};
+
+/// Route of a single ghost, split into the pre-cycle tail and the cycle.
+/// Steps are counted from the start node, which is step 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostPath {
+    pub tail: usize,
+    pub cycle: usize,
+    /// Goal hits before the cycle starts.
+    pub tail_hits: Vec<usize>,
+    /// Goal hits within the first pass of the cycle, `tail <= hit < tail + cycle`.
+    pub cycle_hits: Vec<usize>,
+}
+
+impl GhostPath {
+    pub fn analyze(
+        start: &str,
+        dirs: &[bool],
+        map: &HashMap<&str, (&str, &str)>,
+        is_goal: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut visited = HashMap::new();
+        let mut hits = vec![];
+        let mut pos = start;
+
+        for step in 0.. {
+            let dir_idx = step % dirs.len();
+
+            if let Some(&tail) = visited.get(&(pos, dir_idx)) {
+                let (tail_hits, cycle_hits) = hits.into_iter().partition(|&h| h < tail);
+
+                return GhostPath {
+                    tail,
+                    cycle: step - tail,
+                    tail_hits,
+                    cycle_hits,
+                };
+            }
+
+            visited.insert((pos, dir_idx), step);
+
+            if is_goal(pos) {
+                hits.push(step);
+            }
+
+            let (l, r) = map.get(pos).unwrap();
+            pos = if dirs[dir_idx] { *l } else { *r };
+        }
+
+        unreachable!()
+    }
+
+    pub fn is_hit(&self, step: usize) -> bool {
+        if step < self.tail {
+            self.tail_hits.contains(&step)
+        } else {
+            let in_cycle = self.tail + (step - self.tail) % self.cycle;
+            self.cycle_hits.contains(&in_cycle)
+        }
+    }
+}
+
+/// First step (after the start) at which all ghosts stand on a goal at once.
+pub fn first_common_hit(paths: &[GhostPath]) -> Option<usize> {
+    let longest_tail = paths.iter().max_by_key(|p| p.tail)?;
+
+    // before the longest tail ends, that ghost can only be on its tail hits
+    if let Some(step) = longest_tail
+        .tail_hits
+        .iter()
+        .filter(|&&s| s > 0)
+        .find(|&&s| paths.iter().all(|p| p.is_hit(s)))
+    {
+        return Some(*step);
+    }
+
+    let min_step = longest_tail.tail.max(1) as i128;
+
+    paths
+        .iter()
+        .map(|p| {
+            p.cycle_hits
+                .iter()
+                .map(|&h| Congruence::new(h as i128, p.cycle as i128))
+                .collect_vec()
+        })
+        .multi_cartesian_product()
+        .filter_map(crt)
+        .map(|c| c.first_from(min_step) as usize)
+        .min()
+}
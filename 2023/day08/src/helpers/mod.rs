@@ -0,0 +1,2 @@
+pub mod ghost;
+pub mod number_theory;
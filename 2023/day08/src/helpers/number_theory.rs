@@ -0,0 +1,49 @@
+use num::Integer;
+
+/// Solution of `x ≡ residue (mod modulus)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: i128) -> Self {
+        Congruence {
+            residue: residue.rem_euclid(modulus),
+            modulus,
+        }
+    }
+
+    /// Combines two congruences, the moduli don't need to be coprime.
+    /// Returns `None` when no number satisfies both.
+    pub fn combine(&self, other: &Congruence) -> Option<Congruence> {
+        let egcd = self.modulus.extended_gcd(&other.modulus);
+        let (mult, rem) = (other.residue - self.residue).div_rem(&egcd.gcd);
+
+        if rem != 0 {
+            return None;
+        }
+
+        let lcm = self.modulus / egcd.gcd * other.modulus;
+        let step = (mult * egcd.x).rem_euclid(other.modulus / egcd.gcd);
+
+        Some(Congruence::new(self.residue + step * self.modulus, lcm))
+    }
+
+    /// Smallest satisfying number that is at least `min`.
+    pub fn first_from(&self, min: i128) -> i128 {
+        if self.residue >= min {
+            self.residue
+        } else {
+            self.residue + (min - self.residue + self.modulus - 1) / self.modulus * self.modulus
+        }
+    }
+}
+
+/// Chinese remainder theorem over any (not necessarily coprime) moduli.
+pub fn crt(congruences: impl IntoIterator<Item = Congruence>) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), |acc, c| acc.combine(&c))
+}
@@ -1,9 +1,11 @@
 #![feature(test)]
 
+mod helpers;
+
 use std::{collections::HashMap, fs};
 
+use helpers::ghost::{first_common_hit, GhostPath};
 use itertools::Itertools;
-use num::Integer;
 
 fn main() {
     let input = fs::read_to_string("./day08/_data/input.txt").expect("oh noes");
@@ -32,34 +34,14 @@ fn process_part_1(input: &str) -> String {
 fn process_part_2(input: &str) -> String {
     let (dirs, map) = parse(input);
 
-    map.iter()
-        .filter(|(k, _)| k.ends_with('A'))
-        .map(|(k, _)| *k)
-        .map(|p: &str| {
-            let mut pos = p;
-            let mut ofs = None;
-
-            for (step, dir) in dirs.iter().cycle().enumerate() {
-                let (l, r) = map.get(pos).unwrap();
-
-                pos = if *dir { *l } else { *r };
-
-                if pos.ends_with('Z') {
-                    if let Some(ofs_step) = ofs {
-                        return (ofs_step + 1, step - ofs_step);
-                    } else {
-                        ofs = Some(step);
-                    }
-                }
-            }
-
-            panic!("no directions found")
-        })
-        .sorted()
-        .map(|(x, y)| (x as isize, y as isize))
-        .reduce(combine_phases)
-        .map(|(ofs, per)| ofs + per)
-        .unwrap()
+    let paths = map
+        .keys()
+        .filter(|k| k.ends_with('A'))
+        .map(|k| GhostPath::analyze(k, &dirs, &map, |p| p.ends_with('Z')))
+        .collect_vec();
+
+    first_common_hit(&paths)
+        .expect("ghosts never meet")
         .to_string()
 }
 
@@ -86,30 +68,6 @@ fn parse(input: &str) -> (Vec<bool>, HashMap<&str, (&str, &str)>) {
     )
 }
 
-fn combine_phases((ofs1, per1): (isize, isize), (ofs2, per2): (isize, isize)) -> (isize, isize) {
-    let ofs1 = ofs1 % per1;
-    let ofs2 = ofs2 % per2;
-
-    let (ofs1, per1, ofs2, per2) = if ofs1 > ofs2 {
-        (ofs2, per2, ofs1, per1)
-    } else {
-        (ofs1, per1, ofs2, per2)
-    };
-
-    let egcd = per1.extended_gcd(&per2);
-    let (mult, rem) = (ofs1 - ofs2).rem_euclid(per2).div_rem(&egcd.gcd);
-    if rem > 0 {
-        panic!("won't")
-    }
-
-    let comb_per = per1.lcm(&per2);
-
-    let comb_ofs = (egcd.x * mult * per1).rem_euclid(comb_per);
-    let comb_ofs = (-comb_ofs).rem_euclid(comb_per);
-
-    (comb_ofs, comb_per)
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -149,6 +107,63 @@ XXX = (XXX, XXX)";
         assert_eq!(expected, process_part_2(input));
     }
 
+    const TEST_CASE_3: &str = "
+L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11C, XXX)
+11C = (11D, XXX)
+11D = (11Z, XXX)
+22A = (22B, XXX)
+22B = (22C, XXX)
+22C = (22D, XXX)
+22D = (22Z, XXX)
+22Z = (22E, XXX)
+22E = (22F, XXX)
+22F = (22G, XXX)
+22G = (22Z, XXX)
+XXX = (XXX, XXX)";
+
+    #[rstest]
+    #[case(TEST_CASE_3, "8")]
+    fn part_2_offset_check(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, process_part_2(input));
+    }
+
+    #[rstest]
+    #[case((2, 4), (3, 6), None)]
+    #[case((2, 4), (0, 6), Some((6, 12)))]
+    #[case((2, 3), (3, 5), Some((8, 15)))]
+    #[case((1, 4), (3, 6), Some((9, 12)))]
+    fn crt_check(
+        #[case] a: (i128, i128),
+        #[case] b: (i128, i128),
+        #[case] expected: Option<(i128, i128)>,
+    ) {
+        use helpers::number_theory::{crt, Congruence};
+
+        let res = crt([Congruence::new(a.0, a.1), Congruence::new(b.0, b.1)]);
+
+        assert_eq!(expected, res.map(|c| (c.residue, c.modulus)));
+    }
+
+    #[test]
+    fn ghost_path_check() {
+        let (dirs, map) = parse(TEST_CASE_3);
+        let path = GhostPath::analyze("11A", &dirs, &map, |p| p.ends_with('Z'));
+
+        assert_eq!(
+            GhostPath {
+                tail: 2,
+                cycle: 3,
+                tail_hits: vec![],
+                cycle_hits: vec![2],
+            },
+            path
+        );
+    }
+
     #[rstest]
     #[case("21797")]
     fn part_1_control(#[case] expected: &str) {
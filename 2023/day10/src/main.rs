@@ -1,276 +1,37 @@
 #![feature(test)]
 
-use std::{collections::HashMap, fs};
+mod pipe_loop;
 
-use itertools::Itertools;
+use std::fs;
+
+use pipe_loop::PipeLoop;
 
 fn main() {
     let input = fs::read_to_string("./day10/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
-}
-
-fn process_part_1(input: &str) -> String {
-    (get_main_pipe(input).len() / 2).to_string()
-}
-
-fn process_part_2(input: &str) -> String {
-    let rows = input.lines().filter(|l| !l.is_empty()).count() as isize;
-    let cols = input.lines().find(|l| !l.is_empty()).unwrap().len() as isize;
-    let pipe = get_main_pipe(input);
-
-    let mut tiles = HashMap::<Pos, i32>::new();
-
-    // it would be probably better to count from non-pipe tiles out, but whatever
-    for (pos, dir) in pipe {
-        tiles
-            .entry(pos)
-            .and_modify(|c| *c += 100_000)
-            .or_insert(100_000);
-
-        for d in dir {
-            match d {
-                Dir::East => {
-                    for r in 0..pos.row {
-                        tiles
-                            .entry(Pos {
-                                row: r,
-                                col: pos.col,
-                            })
-                            .and_modify(|c| *c -= 1)
-                            .or_insert(-1);
-                    }
-                    for r in (pos.row + 1)..rows {
-                        tiles
-                            .entry(Pos {
-                                row: r,
-                                col: pos.col,
-                            })
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                    }
-                }
-                Dir::South => {
-                    for c in 0..pos.col {
-                        tiles
-                            .entry(Pos {
-                                row: pos.row,
-                                col: c,
-                            })
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                    }
-                    for c in (pos.col + 1)..cols {
-                        tiles
-                            .entry(Pos {
-                                row: pos.row,
-                                col: c,
-                            })
-                            .and_modify(|c| *c -= 1)
-                            .or_insert(-1);
-                    }
-                }
-                Dir::West => {
-                    for r in 0..pos.row {
-                        tiles
-                            .entry(Pos {
-                                row: r,
-                                col: pos.col,
-                            })
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                    }
-                    for r in (pos.row + 1)..rows {
-                        tiles
-                            .entry(Pos {
-                                row: r,
-                                col: pos.col,
-                            })
-                            .and_modify(|c| *c -= 1)
-                            .or_insert(-1);
-                    }
-                }
-                Dir::North => {
-                    for c in 0..pos.col {
-                        tiles
-                            .entry(Pos {
-                                row: pos.row,
-                                col: c,
-                            })
-                            .and_modify(|c| *c -= 1)
-                            .or_insert(-1);
-                    }
-                    for c in (pos.col + 1)..cols {
-                        tiles
-                            .entry(Pos {
-                                row: pos.row,
-                                col: c,
-                            })
-                            .and_modify(|c| *c += 1)
-                            .or_insert(1);
-                    }
-                }
-            }
-        }
-    }
-
-    tiles
-        .values()
-        .filter(|v| **v == 8 || **v == -8)
-        .count()
-        .to_string()
-}
 
-fn get_main_pipe(input: &str) -> Vec<(Pos, Vec<Dir>)> {
-    let tiles: HashMap<Pos, Tile> = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.chars()
-                .enumerate()
-                .filter(|(_, c)| *c != '.')
-                .map(|(col, c)| {
-                    (
-                        Pos {
-                            row: row as isize,
-                            col: col as isize,
-                        },
-                        match c {
-                            '|' => Tile::Pipe([Dir::North, Dir::South]),
-                            '-' => Tile::Pipe([Dir::West, Dir::East]),
-                            'L' => Tile::Pipe([Dir::North, Dir::East]),
-                            'J' => Tile::Pipe([Dir::North, Dir::West]),
-                            '7' => Tile::Pipe([Dir::West, Dir::South]),
-                            'F' => Tile::Pipe([Dir::East, Dir::South]),
-                            'S' => Tile::Start,
-                            x => panic!("{:?}", x),
-                        },
-                    )
-                })
-                .collect_vec()
-        })
-        .collect();
-
-    let start = tiles
-        .iter()
-        .find(|(_, tile)| Tile::Start == **tile)
-        .unwrap();
-
-    let (mut dir, mut pos) = get_start_conn(&tiles, start.0);
-    let start_dir = dir;
-
-    let mut main_pipe = Vec::<(Pos, Vec<Dir>)>::new();
-
-    loop {
-        if let Tile::Pipe(v) = pos.1 {
-            let (r, c, new_dir) = match v.iter().find(|d| dir != **d).unwrap() {
-                Dir::East => (0, 1, Dir::West),
-                Dir::South => (1, 0, Dir::North),
-                Dir::West => (0, -1, Dir::East),
-                Dir::North => (-1, 0, Dir::South),
-            };
-
-            main_pipe.push((pos.0, vec![dir.opposite(), new_dir.opposite()]));
-
-            let next_pos = Pos {
-                row: pos.0.row + r,
-                col: pos.0.col + c,
-            };
-
-            let next_tile = tiles.get(&next_pos).unwrap();
-
-            pos = (next_pos, next_tile);
-            dir = new_dir;
-        } else {
-            main_pipe.push((*start.0, vec![start_dir.opposite(), dir.opposite()]));
-            return main_pipe;
-        }
-    }
-}
-
-fn get_start_conn<'a>(tiles: &'a HashMap<Pos, Tile>, start: &Pos) -> (Dir, (Pos, &'a Tile)) {
-    let east = Pos {
-        row: start.row,
-        col: start.col + 1,
-    };
-    if let Some(tile) = tiles.get(&east) {
-        if let Tile::Pipe(v) = tile {
-            if v.iter().any(|d| Dir::West == *d) {
-                return (Dir::West, (east, tile));
-            }
-        }
-    }
-
-    let south = Pos {
-        row: start.row + 1,
-        col: start.col,
-    };
-    if let Some(tile) = tiles.get(&south) {
-        if let Tile::Pipe(v) = tile {
-            if v.iter().any(|d| Dir::North == *d) {
-                return (Dir::North, (south, tile));
-            }
-        }
-    }
-
-    let west = Pos {
-        row: start.row,
-        col: start.col - 1,
-    };
-    if let Some(tile) = tiles.get(&west) {
-        if let Tile::Pipe(v) = tile {
-            if v.iter().any(|d| Dir::East == *d) {
-                return (Dir::East, (west, tile));
-            }
-        }
-    }
+    if cfg!(feature = "report") {
+        let pipe_loop = PipeLoop::try_from(input.as_str()).unwrap();
 
-    let north = Pos {
-        row: start.row - 1,
-        col: start.col,
-    };
-    if let Some(tile) = tiles.get(&north) {
-        if let Tile::Pipe(v) = tile {
-            if v.iter().any(|d| Dir::South == *d) {
-                return (Dir::South, (north, tile));
-            }
-        }
+        println!("{}", pipe_loop.render());
+        println!(
+            "start is {:?}, {} corners",
+            pipe_loop.start_pipe(),
+            pipe_loop.vertices().len()
+        );
     }
-
-    panic!("start is not connected")
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, PartialOrd, Ord)]
-struct Pos {
-    row: isize,
-    col: isize,
-}
-
-#[derive(PartialEq, Debug)]
-enum Tile {
-    Start,
-    Pipe([Dir; 2]),
-}
+fn process_part_1(input: &str) -> String {
+    let pipe_loop = PipeLoop::try_from(input).unwrap();
 
-#[derive(PartialEq, Clone, Copy, Debug)]
-enum Dir {
-    East,
-    South,
-    West,
-    North,
+    (pipe_loop.tiles().len() / 2).to_string()
 }
 
-impl Dir {
-    fn opposite(&self) -> Dir {
-        match self {
-            Dir::East => Dir::West,
-            Dir::South => Dir::North,
-            Dir::West => Dir::East,
-            Dir::North => Dir::South,
-        }
-    }
+fn process_part_2(input: &str) -> String {
+    PipeLoop::try_from(input).unwrap().interior().to_string()
 }
 
 #[cfg(test)]
@@ -345,6 +106,62 @@ L7JLJL-JLJLJL--JLJ.L";
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case(TEST_CASE_1, 'F')]
+    #[case(TEST_CASE_2, 'F')]
+    #[case(TEST_CASE_4, 'F')]
+    #[case(TEST_CASE_5, '7')]
+    fn start_pipe_check(#[case] input: &str, #[case] expected: char) {
+        assert_eq!(expected, PipeLoop::try_from(input).unwrap().start_pipe());
+    }
+
+    #[test]
+    fn loop_check() {
+        let pipe_loop = PipeLoop::try_from(TEST_CASE_1).unwrap();
+
+        assert_eq!(8, pipe_loop.tiles().len());
+        assert_eq!(
+            vec![(1, 1), (1, 3), (3, 3), (3, 1)],
+            pipe_loop
+                .vertices()
+                .iter()
+                .map(|p| (p.row, p.col))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(8, pipe_loop.polygon().double_area());
+        assert_eq!(1, pipe_loop.interior());
+    }
+
+    #[rstest]
+    #[case(TEST_CASE_3)]
+    #[case(TEST_CASE_4)]
+    #[case(TEST_CASE_5)]
+    fn render_check(#[case] input: &str) {
+        let pipe_loop = PipeLoop::try_from(input).unwrap();
+        let render = pipe_loop.render();
+
+        assert_eq!(pipe_loop.interior(), render.matches('I').count());
+        assert_eq!(
+            pipe_loop.tiles().len(),
+            render.chars().filter(|c| "│─└┘┐┌".contains(*c)).count()
+        );
+    }
+
+    #[test]
+    fn render_small_check() {
+        let expected = ".....\n.┌─┐.\n.│I│.\n.└─┘.\n.....";
+
+        assert_eq!(expected, PipeLoop::try_from(TEST_CASE_1).unwrap().render());
+    }
+
+    #[rstest]
+    #[case("...\n.S.\n...", "Start is not on a loop")]
+    #[case("F7\nL7", "No start tile")]
+    #[case("S7\nLx", "Unknown tile: 'x'")]
+    fn invalid_check(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(Some(expected.to_owned()), PipeLoop::try_from(input).err());
+    }
+
     #[rstest]
     #[case("6942")]
     fn part_1_control(#[case] expected: &str) {
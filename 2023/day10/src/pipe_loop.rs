@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use polygon::Polygon;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, PartialOrd, Ord)]
+pub struct Pos {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Pos {
+    fn step(self, dir: Dir) -> Pos {
+        let (r, c) = match dir {
+            Dir::East => (0, 1),
+            Dir::South => (1, 0),
+            Dir::West => (0, -1),
+            Dir::North => (-1, 0),
+        };
+
+        Pos {
+            row: self.row + r,
+            col: self.col + c,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Dir {
+    East,
+    South,
+    West,
+    North,
+}
+
+impl Dir {
+    const ALL: [Dir; 4] = [Dir::East, Dir::South, Dir::West, Dir::North];
+
+    fn opposite(&self) -> Dir {
+        match self {
+            Dir::East => Dir::West,
+            Dir::South => Dir::North,
+            Dir::West => Dir::East,
+            Dir::North => Dir::South,
+        }
+    }
+}
+
+/// Pipe shapes as puzzle characters, box-drawing characters and the two ends they connect.
+const PIPES: [(char, char, [Dir; 2]); 6] = [
+    ('|', '│', [Dir::North, Dir::South]),
+    ('-', '─', [Dir::West, Dir::East]),
+    ('L', '└', [Dir::North, Dir::East]),
+    ('J', '┘', [Dir::North, Dir::West]),
+    ('7', '┐', [Dir::West, Dir::South]),
+    ('F', '┌', [Dir::East, Dir::South]),
+];
+
+fn pipe(ends: [Dir; 2]) -> &'static (char, char, [Dir; 2]) {
+    PIPES
+        .iter()
+        .find(|(_, _, e)| e.contains(&ends[0]) && e.contains(&ends[1]))
+        .unwrap()
+}
+
+/// The main pipe loop through the start tile, whose pipe is worked out from
+/// the tiles around it.
+#[derive(Debug, Clone)]
+pub struct PipeLoop {
+    /// Loop tiles in walking order, beginning with the start.
+    tiles: Vec<Pos>,
+    /// Ends of every loop tile, the start included.
+    ends: HashMap<Pos, [Dir; 2]>,
+    rows: isize,
+    cols: isize,
+}
+
+impl TryFrom<&str> for PipeLoop {
+    type Error = String;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let lines = input.lines().filter(|l| !l.is_empty()).collect_vec();
+        let mut pipes = HashMap::new();
+        let mut start = None;
+
+        for (row, line) in lines.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let pos = Pos {
+                    row: row as isize,
+                    col: col as isize,
+                };
+
+                match ch {
+                    '.' => {}
+                    'S' => start = Some(pos),
+                    _ => {
+                        let (_, _, ends) = PIPES
+                            .iter()
+                            .find(|(c, _, _)| *c == ch)
+                            .ok_or_else(|| format!("Unknown tile: {:?}", ch))?;
+                        pipes.insert(pos, *ends);
+                    }
+                }
+            }
+        }
+
+        let start = start.ok_or("No start tile")?;
+
+        // neighbours pointing back at the start, any two of them may close the loop
+        let open = Dir::ALL
+            .into_iter()
+            .filter(|d| {
+                pipes
+                    .get(&start.step(*d))
+                    .is_some_and(|ends| ends.contains(&d.opposite()))
+            })
+            .collect_vec();
+
+        let (tiles, start_ends) = open
+            .iter()
+            .tuple_combinations()
+            .find_map(|(a, b)| walk(&pipes, start, *a, *b).map(|tiles| (tiles, [*a, *b])))
+            .ok_or("Start is not on a loop")?;
+
+        pipes.insert(start, start_ends);
+        let ends = tiles.iter().map(|pos| (*pos, pipes[pos])).collect();
+
+        Ok(PipeLoop {
+            tiles,
+            ends,
+            rows: lines.len() as isize,
+            cols: lines.iter().map(|l| l.len()).max().unwrap_or(0) as isize,
+        })
+    }
+}
+
+/// Tiles from `start` leaving through `out` until it comes back in through `back`.
+fn walk(pipes: &HashMap<Pos, [Dir; 2]>, start: Pos, out: Dir, back: Dir) -> Option<Vec<Pos>> {
+    let mut tiles = vec![start];
+    let (mut pos, mut dir) = (start.step(out), out);
+
+    while pos != start {
+        let ends = pipes.get(&pos)?;
+        if !ends.contains(&dir.opposite()) {
+            return None;
+        }
+
+        tiles.push(pos);
+        dir = *ends.iter().find(|d| **d != dir.opposite())?;
+        pos = pos.step(dir);
+    }
+
+    (dir == back.opposite()).then_some(tiles)
+}
+
+impl PipeLoop {
+    /// Tiles of the loop in walking order, beginning with the start.
+    pub fn tiles(&self) -> &[Pos] {
+        &self.tiles
+    }
+
+    /// Puzzle character of the pipe hidden under the start tile.
+    pub fn start_pipe(&self) -> char {
+        pipe(self.ends[&self.tiles[0]]).0
+    }
+
+    /// Corner tiles in walking order, the vertices of the loop as a polygon.
+    pub fn vertices(&self) -> Vec<Pos> {
+        self.tiles
+            .iter()
+            .filter(|pos| {
+                let [a, b] = self.ends[pos];
+                a != b.opposite()
+            })
+            .copied()
+            .collect_vec()
+    }
+
+    /// Loop through the tile centres as a lattice polygon, `x` being the column.
+    pub fn polygon(&self) -> Polygon<i64> {
+        self.vertices()
+            .iter()
+            .map(|pos| (pos.col as i64, pos.row as i64))
+            .collect()
+    }
+
+    /// Tiles enclosed by the loop, Pick's theorem with every loop tile a boundary point.
+    pub fn interior(&self) -> usize {
+        self.polygon().interior_points() as usize
+    }
+
+    /// The grid with the loop in box-drawing characters, `I` on enclosed
+    /// tiles and `.` everywhere else.
+    pub fn render(&self) -> String {
+        (0..self.rows)
+            .map(|row| {
+                // a row crosses the loop on every tile connected to the north
+                let mut inside = false;
+
+                (0..self.cols)
+                    .map(|col| match self.ends.get(&Pos { row, col }) {
+                        Some(ends) => {
+                            inside ^= ends.contains(&Dir::North);
+                            pipe(*ends).1
+                        }
+                        None if inside => 'I',
+                        None => '.',
+                    })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
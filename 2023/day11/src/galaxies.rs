@@ -0,0 +1,73 @@
+use itertools::Itertools;
+
+/// Galaxy positions split per axis, each axis kept sorted. Distances are
+/// Manhattan ones so rows and columns are summed independently.
+#[derive(Debug, Clone)]
+pub struct Galaxies {
+    rows: Vec<u64>,
+    cols: Vec<u64>,
+    height: u64,
+    width: u64,
+}
+
+impl From<&str> for Galaxies {
+    fn from(input: &str) -> Self {
+        let lines = input.lines().filter(|l| !l.is_empty()).collect_vec();
+        let (rows, cols): (Vec<_>, Vec<_>) = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(row, line)| {
+                line.char_indices()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(col, _)| (row as u64, col as u64))
+            })
+            .unzip();
+
+        Galaxies {
+            rows,
+            cols: cols.into_iter().sorted_unstable().collect(),
+            height: lines.len() as u64,
+            width: lines.iter().map(|l| l.len()).max().unwrap_or(0) as u64,
+        }
+    }
+}
+
+impl Galaxies {
+    /// Sum of distances over all pairs, every empty row growing into
+    /// `row_factor` rows and every empty column into `col_factor` columns.
+    pub fn total_distance(&self, row_factor: u64, col_factor: u64) -> u128 {
+        axis_distance(&self.rows, self.height, row_factor)
+            + axis_distance(&self.cols, self.width, col_factor)
+    }
+}
+
+/// Pairwise distances along one axis of sorted `coords`. Expanding keeps the
+/// order, so the `i`-th coordinate is `i` times bigger than the ones before it,
+/// less their prefix sum.
+fn axis_distance(coords: &[u64], size: u64, factor: u64) -> u128 {
+    let mut occupied = vec![false; size as usize];
+    for &c in coords {
+        occupied[c as usize] = true;
+    }
+    let empty_before = occupied
+        .iter()
+        .scan(0u64, |empty, &o| {
+            let before = *empty;
+            *empty += !o as u64;
+            Some(before)
+        })
+        .collect_vec();
+
+    let mut prefix = 0u128;
+    coords
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let empty = empty_before[c as usize] as u128;
+            let x = c as u128 - empty + empty * factor as u128;
+            let dist = x * i as u128 - prefix;
+            prefix += x;
+            dist
+        })
+        .sum()
+}
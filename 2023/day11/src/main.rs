@@ -1,9 +1,10 @@
-#![feature(cmp_minmax)]
 #![feature(test)]
 
-use std::{cmp, collections::HashSet, fs};
+mod galaxies;
 
-use itertools::Itertools;
+use std::fs;
+
+use crate::galaxies::Galaxies;
 
 fn main() {
     let input = fs::read_to_string("./day11/_data/input.txt").expect("oh noes");
@@ -13,82 +14,19 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    process(input, 2)
+    process(input, 2, 2)
 }
 
-fn process_part_2(input: &str, expand_ratio: isize) -> String {
-    process(input, expand_ratio)
+fn process_part_2(input: &str, expand_ratio: u64) -> String {
+    process(input, expand_ratio, expand_ratio)
 }
 
-fn process(input: &str, expand_ratio: isize) -> String {
-    let field = input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.char_indices().filter_map(move |(col, c)| {
-                if c == '#' {
-                    Some((row as isize, col as isize))
-                } else {
-                    None
-                }
-            })
-        })
-        .collect_vec();
-
-    let rows = input.lines().filter(|l| !l.is_empty()).count() as isize;
-    let cols = input.lines().find(|l| !l.is_empty()).unwrap().len() as isize;
-    let mut empty_rows = HashSet::<isize>::from_iter(0..rows);
-    let mut empty_cols = HashSet::<isize>::from_iter(0..cols);
-
-    for (row, col) in field.iter() {
-        empty_rows.remove(row);
-        empty_cols.remove(col);
-    }
-
-    let empty_rows = empty_rows.into_iter().sorted().collect_vec();
-    let empty_cols = empty_cols.into_iter().sorted().collect_vec();
-
-    field
-        .iter()
-        .enumerate()
-        .flat_map(|(i, v1)| field.iter().skip(i + 1).map(|v2| (*v1, *v2)))
-        .map(|(v1, v2)| get_dist(v1, v2, &empty_rows, &empty_cols, expand_ratio))
-        .sum::<isize>()
+fn process(input: &str, row_factor: u64, col_factor: u64) -> String {
+    Galaxies::from(input)
+        .total_distance(row_factor, col_factor)
         .to_string()
 }
 
-fn get_dist(
-    v1: (isize, isize),
-    v2: (isize, isize),
-    empty_rows: &[isize],
-    empty_cols: &[isize],
-    expand_ratio: isize,
-) -> isize {
-    let (row1, col1) = v1;
-    let (row2, col2) = v2;
-    let [row1, row2] = cmp::minmax(row1, row2);
-    let [col1, col2] = cmp::minmax(col1, col2);
-
-    let r_dist = row2 - row1
-        + (expand_ratio - 1)
-            * (empty_rows
-                .iter()
-                .skip_while(|r| **r < row1)
-                .take_while(|r| **r < row2)
-                .count() as isize);
-
-    let c_dist = col2 - col1
-        + (expand_ratio - 1)
-            * (empty_cols
-                .iter()
-                .skip_while(|r| **r < col1)
-                .take_while(|r| **r < col2)
-                .count() as isize);
-
-    r_dist + c_dist
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -118,10 +56,82 @@ mod tests {
     #[rstest]
     #[case(TEST_CASE, 10, "1030")]
     #[case(TEST_CASE, 100, "8410")]
-    fn part_2_check(#[case] input: &str, #[case] expand_ratio: isize, #[case] expected: &str) {
+    fn part_2_check(#[case] input: &str, #[case] expand_ratio: u64, #[case] expected: &str) {
         assert_eq!(expected, process_part_2(input, expand_ratio));
     }
 
+    /// Expanded positions and the plain pairwise sum, as it used to be done.
+    fn brute(input: &str, row_factor: u64, col_factor: u64) -> u128 {
+        let lines = input.lines().filter(|l| !l.is_empty()).collect::<Vec<_>>();
+        let galaxies = lines
+            .iter()
+            .enumerate()
+            .flat_map(|(r, l)| {
+                l.char_indices()
+                    .filter(|(_, c)| *c == '#')
+                    .map(move |(c, _)| (r, c))
+            })
+            .collect::<Vec<_>>();
+        let expand = |n: usize, factor: u64, empty: &dyn Fn(usize) -> bool| {
+            (0..n)
+                .map(|i| if empty(i) { factor as u128 } else { 1 })
+                .collect::<Vec<_>>()
+        };
+        let row_sizes = expand(lines.len(), row_factor, &|r| !lines[r].contains('#'));
+        let col_sizes = expand(lines[0].len(), col_factor, &|c| {
+            lines.iter().all(|l| l.as_bytes()[c] != b'#')
+        });
+        let span =
+            |sizes: &[u128], a: usize, b: usize| sizes[a.min(b)..a.max(b)].iter().sum::<u128>();
+
+        let mut total = 0;
+        for (i, a) in galaxies.iter().enumerate() {
+            for b in galaxies.iter().skip(i + 1) {
+                total += span(&row_sizes, a.0, b.0) + span(&col_sizes, a.1, b.1);
+            }
+        }
+        total
+    }
+
+    #[rstest]
+    #[case(2, 2)]
+    #[case(1, 1)]
+    #[case(0, 0)]
+    #[case(3, 7)]
+    #[case(1_000_000, 1)]
+    fn factors_check(#[case] row_factor: u64, #[case] col_factor: u64) {
+        let galaxies = Galaxies::from(TEST_CASE);
+
+        assert_eq!(
+            brute(TEST_CASE, row_factor, col_factor),
+            galaxies.total_distance(row_factor, col_factor)
+        );
+    }
+
+    #[test]
+    fn big_factor_check() {
+        // every pair grows linearly with the factor, past what a u64 holds here
+        let input = (0..300)
+            .map(|r| {
+                if r % 2 == 0 {
+                    "#.".repeat(150)
+                } else {
+                    ".".repeat(300)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let galaxies = Galaxies::from(input.as_str());
+        let factor = 1_000_000_000_000u64;
+
+        let base = galaxies.total_distance(1, 1);
+        let step = galaxies.total_distance(2, 2) - base;
+        let total = galaxies.total_distance(factor, factor);
+
+        assert!(total > u64::MAX as u128);
+        assert_eq!(base + (factor as u128 - 1) * step, total);
+    }
+
     #[rstest]
     #[case("9681886")]
     fn part_1_control(#[case] expected: &str) {
@@ -1,10 +1,12 @@
 #![feature(test)]
 
+mod springs;
+
 use std::fs;
 
-use cached::proc_macro::cached;
 use itertools::Itertools;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use springs::{count_arrangements, unfold};
 
 fn main() {
     let input = fs::read_to_string("./day12/_data/input.txt").expect("oh noes");
@@ -14,108 +16,48 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    input
-        .lines()
-        .filter(|l| !l.is_empty())
-        .collect_vec()
+    process(input, 1)
+}
+
+fn process_part_2(input: &str) -> String {
+    process(input, 5)
+}
+
+fn process(input: &str, factor: usize) -> String {
+    parse(input)
         .par_iter()
-        .map(|line| line.split_ascii_whitespace().next_tuple().unwrap())
-        .map(|(pat, nums)| {
-            generate_poss(
-                nums.split(',')
-                    .map(|s| s.parse::<usize>().unwrap())
-                    .collect_vec(),
-                pat.chars().map_into().collect_vec(),
-            )
+        .map(|(pattern, groups)| {
+            let (pattern, groups) = unfold(pattern, groups, factor);
+            count_arrangements(&pattern, &groups)
         })
-        .sum::<usize>()
+        .sum::<u128>()
         .to_string()
 }
 
-fn process_part_2(input: &str) -> String {
+fn parse(input: &str) -> Vec<(String, Vec<usize>)> {
     input
         .lines()
         .filter(|l| !l.is_empty())
-        .collect_vec()
-        .par_iter()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .next_tuple()
-                .map(|(pat, nums)| ([pat].repeat(5).join("?"), [nums].repeat(5).join(",")))
-                .unwrap()
-        })
-        .map(|(pat, nums)| {
-            generate_poss(
-                nums.split(',')
+        .map(|line| line.split_ascii_whitespace().next_tuple().unwrap())
+        .map(|(pattern, groups)| {
+            (
+                pattern.to_owned(),
+                groups
+                    .split(',')
                     .map(|s| s.parse::<usize>().unwrap())
                     .collect_vec(),
-                pat.chars().map_into().collect_vec(),
             )
         })
-        .sum::<usize>()
-        .to_string()
-}
-
-#[cached]
-fn generate_poss(nums: Vec<usize>, filter: Vec<Spring>) -> usize {
-    if let Some((head, tail)) = nums.split_first() {
-        let rem = tail.iter().sum::<usize>() + tail.len();
-        let len = filter.len();
-        let max = len - rem - head;
-
-        if tail.is_empty() {
-            (0..=max)
-                .filter(|i| matches_filter(*i, *head, len - i - head, &filter))
-                .count()
-        } else {
-            (0..=max)
-                .filter(|i| (i + head + rem) <= len)
-                .filter(|i| matches_filter(*i, *head, 1, &filter))
-                .collect_vec()
-                .par_iter()
-                .map(|i| generate_poss(tail.to_owned(), filter[(i + head + 1)..].to_owned()))
-                .sum::<usize>()
-        }
-    } else {
-        0
-    }
-}
-
-fn matches_filter(prefix: usize, group: usize, suffix: usize, filter: &[Spring]) -> bool {
-    [
-        vec![Spring::Operational; prefix],
-        vec![Spring::Damaged; group],
-        vec![Spring::Operational; suffix],
-    ]
-    .concat()
-    .into_iter()
-    .zip(filter)
-    .all(|(a, b)| *b == Spring::Unknown || *b == a)
-}
-
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-enum Spring {
-    Unknown,
-    Damaged,
-    Operational,
-}
-
-impl From<char> for Spring {
-    fn from(value: char) -> Self {
-        match value {
-            '?' => Spring::Unknown,
-            '#' => Spring::Damaged,
-            '.' => Spring::Operational,
-            x => panic!("unknown char '{:?}'", x),
-        }
-    }
+        .collect_vec()
 }
 
 #[cfg(test)]
 mod tests {
     extern crate test;
 
+    use crate::springs::Solver;
     use crate::*;
+    use rand::{rngs::StdRng, SeedableRng};
     use rstest::rstest;
     use test::Bencher;
 
@@ -139,6 +81,66 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case("???.###", &[1, 1, 3], 1, 1)]
+    #[case(".??..??...?##.", &[1, 1, 3], 1, 4)]
+    #[case("?###????????", &[3, 2, 1], 1, 10)]
+    #[case("????.######..#####.", &[1, 6, 5], 5, 2500)]
+    #[case("?###????????", &[3, 2, 1], 5, 506250)]
+    #[case("?", &[1], 40, 1)]
+    #[case("??", &[], 1, 1)]
+    #[case("#", &[], 1, 0)]
+    #[case("", &[1], 1, 0)]
+    fn count_arrangements_check(
+        #[case] pattern: &str,
+        #[case] groups: &[usize],
+        #[case] factor: usize,
+        #[case] expected: u128,
+    ) {
+        let (pattern, groups) = unfold(pattern, groups, factor);
+        assert_eq!(expected, count_arrangements(&pattern, &groups));
+    }
+
+    /// Every way of filling the unknowns whose damaged runs match `groups`.
+    fn brute(pattern: &str, groups: &[usize]) -> Vec<String> {
+        pattern
+            .chars()
+            .map(|ch| match ch {
+                '?' => vec!['.', '#'],
+                x => vec![x],
+            })
+            .multi_cartesian_product()
+            .map(|chars| chars.into_iter().collect::<String>())
+            .filter(|s| {
+                s.split('.')
+                    .filter(|run| !run.is_empty())
+                    .map(str::len)
+                    .collect_vec()
+                    == groups
+            })
+            .collect_vec()
+    }
+
+    #[rstest]
+    #[case("?#?#?#?#?#?#?#?", &[1, 3, 1, 6])]
+    #[case("????.#...#...", &[4, 1, 1])]
+    #[case("??????????", &[2, 1, 2])]
+    #[case("?.?#??.??", &[1, 1])]
+    fn arrangements_check(#[case] pattern: &str, #[case] groups: &[usize]) {
+        let solver = Solver::new(pattern, groups);
+        let mut expected = brute(pattern, groups);
+        expected.sort_by_key(|s| s.replace('.', "0").replace('#', "1"));
+
+        assert_eq!(expected.len() as u128, solver.count());
+        assert_eq!(expected, solver.arrangements(usize::MAX));
+        assert_eq!(expected[..1], solver.arrangements(1));
+
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert!(expected.contains(&solver.sample(&mut rng).unwrap()));
+        }
+    }
+
     #[rstest]
     #[case("7017")]
     fn part_1_control(#[case] expected: &str) {
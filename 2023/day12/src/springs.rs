@@ -0,0 +1,203 @@
+use itertools::Itertools;
+#[cfg(test)]
+use rand::Rng;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Spring {
+    Unknown,
+    Damaged,
+    Operational,
+}
+
+impl From<char> for Spring {
+    fn from(value: char) -> Self {
+        match value {
+            '?' => Spring::Unknown,
+            '#' => Spring::Damaged,
+            '.' => Spring::Operational,
+            x => panic!("unknown char '{:?}'", x),
+        }
+    }
+}
+
+impl Spring {
+    /// Concrete springs this one may stand for.
+    fn options(self) -> &'static [Spring] {
+        match self {
+            Spring::Unknown => &[Spring::Operational, Spring::Damaged],
+            Spring::Damaged => &[Spring::Damaged],
+            Spring::Operational => &[Spring::Operational],
+        }
+    }
+
+    #[cfg(test)]
+    fn symbol(self) -> char {
+        match self {
+            Spring::Unknown => '?',
+            Spring::Damaged => '#',
+            Spring::Operational => '.',
+        }
+    }
+}
+
+/// Pattern and groups repeated `factor` times, patterns joined by an unknown spring.
+pub fn unfold(pattern: &str, groups: &[usize], factor: usize) -> (String, Vec<usize>) {
+    ([pattern].repeat(factor).join("?"), groups.repeat(factor))
+}
+
+pub fn count_arrangements(pattern: &str, groups: &[usize]) -> u128 {
+    Solver::new(pattern, groups).count()
+}
+
+/// Counts how many ways are left from every position and state, a state
+/// being `(group, run)`: groups already closed and damaged springs in the
+/// open one. Takes `O(len × (groups + damaged))` for any pattern.
+pub struct Solver {
+    pattern: Vec<Spring>,
+    groups: Vec<usize>,
+    /// `offsets[group] + run` indexes a state, the last group only has run 0.
+    offsets: Vec<usize>,
+    /// `ways[pos][state]` arrangements of `pattern[pos..]` from `state`.
+    ways: Vec<Vec<u128>>,
+}
+
+impl Solver {
+    pub fn new(pattern: &str, groups: &[usize]) -> Self {
+        let pattern = pattern.chars().map_into().collect_vec();
+        let offsets = groups
+            .iter()
+            .scan(0, |acc, g| {
+                let offset = *acc;
+                *acc += g + 1;
+                Some(offset)
+            })
+            .chain([groups.iter().map(|g| g + 1).sum()])
+            .collect_vec();
+
+        let mut solver = Solver {
+            groups: groups.to_vec(),
+            ways: vec![vec![0; offsets[groups.len()] + 1]; pattern.len() + 1],
+            pattern,
+            offsets,
+        };
+        let states = solver.states().collect_vec();
+
+        for &state in states.iter() {
+            let idx = solver.index(state);
+            solver.ways[solver.pattern.len()][idx] = solver.accepting(state) as u128;
+        }
+
+        for pos in (0..solver.pattern.len()).rev() {
+            for &state in states.iter() {
+                let ways = solver.pattern[pos]
+                    .options()
+                    .iter()
+                    .filter_map(|&spring| solver.next(state, spring))
+                    .map(|next| solver.ways[pos + 1][solver.index(next)])
+                    .sum();
+                let idx = solver.index(state);
+                solver.ways[pos][idx] = ways;
+            }
+        }
+
+        solver
+    }
+
+    pub fn count(&self) -> u128 {
+        self.ways[0][0]
+    }
+
+    /// Up to `limit` arrangements in lexicographic order, `.` before `#`.
+    #[cfg(test)]
+    pub fn arrangements(&self, limit: usize) -> Vec<String> {
+        let mut res = vec![];
+        let mut stack = vec![(0, (0, 0), String::new())];
+
+        while let Some((pos, state, prefix)) = stack.pop() {
+            if res.len() == limit {
+                break;
+            }
+
+            if pos == self.pattern.len() {
+                res.push(prefix);
+                continue;
+            }
+
+            // pushed in reverse so the operational choice is popped first
+            for &spring in self.pattern[pos].options().iter().rev() {
+                if let Some(next) = self
+                    .next(state, spring)
+                    .filter(|&next| self.ways[pos + 1][self.index(next)] > 0)
+                {
+                    stack.push((pos + 1, next, format!("{}{}", prefix, spring.symbol())));
+                }
+            }
+        }
+
+        res
+    }
+
+    /// One arrangement drawn uniformly at random, `None` if there are none.
+    #[cfg(test)]
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<String> {
+        if self.count() == 0 {
+            return None;
+        }
+
+        let mut state = (0, 0);
+        let mut res = String::new();
+
+        for pos in 0..self.pattern.len() {
+            let choices = self.pattern[pos]
+                .options()
+                .iter()
+                .filter_map(|&spring| {
+                    self.next(state, spring)
+                        .map(|next| (spring, next, self.ways[pos + 1][self.index(next)]))
+                })
+                .collect_vec();
+            let mut pick = rng.gen_range(0..choices.iter().map(|c| c.2).sum::<u128>());
+
+            for (spring, next, ways) in choices {
+                if pick < ways {
+                    res.push(spring.symbol());
+                    state = next;
+                    break;
+                }
+                pick -= ways;
+            }
+        }
+
+        Some(res)
+    }
+
+    fn states(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.groups
+            .iter()
+            .enumerate()
+            .flat_map(|(g, &len)| (0..=len).map(move |run| (g, run)))
+            .chain([(self.groups.len(), 0)])
+    }
+
+    fn index(&self, (group, run): (usize, usize)) -> usize {
+        self.offsets[group] + run
+    }
+
+    fn accepting(&self, (group, run): (usize, usize)) -> bool {
+        let total = self.groups.len();
+
+        (group == total && run == 0) || (group + 1 == total && run == self.groups[group])
+    }
+
+    /// State after one concrete spring, `None` if the groups rule it out.
+    fn next(&self, (group, run): (usize, usize), spring: Spring) -> Option<(usize, usize)> {
+        match spring {
+            Spring::Operational if run == 0 => Some((group, 0)),
+            Spring::Operational if run == self.groups[group] => Some((group + 1, 0)),
+            Spring::Damaged if group < self.groups.len() && run < self.groups[group] => {
+                Some((group, run + 1))
+            }
+            _ => None,
+        }
+    }
+}
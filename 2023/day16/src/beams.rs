@@ -0,0 +1,315 @@
+use itertools::Itertools;
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+}
+
+enum MaybePair<T> {
+    Single(T),
+    Pair((T, T)),
+}
+
+impl<T> MaybePair<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            MaybePair::Single(d) => vec![d],
+            MaybePair::Pair((a, b)) => vec![a, b],
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Tile {
+    Empty,
+    MirrorUp,
+    MirrorDown,
+    SplitHorz,
+    SplitVert,
+}
+
+impl Tile {
+    fn interact(&self, dir: Dir) -> MaybePair<Dir> {
+        match self {
+            Tile::Empty => MaybePair::Single(dir),
+            Tile::MirrorUp => match dir {
+                Dir::Up => MaybePair::Single(Dir::Right),
+                Dir::Right => MaybePair::Single(Dir::Up),
+                Dir::Down => MaybePair::Single(Dir::Left),
+                Dir::Left => MaybePair::Single(Dir::Down),
+            },
+            Tile::MirrorDown => match dir {
+                Dir::Up => MaybePair::Single(Dir::Left),
+                Dir::Right => MaybePair::Single(Dir::Down),
+                Dir::Down => MaybePair::Single(Dir::Right),
+                Dir::Left => MaybePair::Single(Dir::Up),
+            },
+            Tile::SplitHorz => match dir {
+                Dir::Up | Dir::Down => MaybePair::Pair((Dir::Left, Dir::Right)),
+                Dir::Left | Dir::Right => MaybePair::Single(dir),
+            },
+            Tile::SplitVert => match dir {
+                Dir::Up | Dir::Down => MaybePair::Single(dir),
+                Dir::Left | Dir::Right => MaybePair::Pair((Dir::Up, Dir::Down)),
+            },
+        }
+    }
+}
+
+impl From<char> for Tile {
+    fn from(value: char) -> Self {
+        match value {
+            '.' => Tile::Empty,
+            '/' => Tile::MirrorUp,
+            '\\' => Tile::MirrorDown,
+            '-' => Tile::SplitHorz,
+            '|' => Tile::SplitVert,
+            x => panic!("unknown tile: {}", x),
+        }
+    }
+}
+
+/// Every `(cell, direction)` a beam can enter with, linked to the states it
+/// moves on to and condensed into strongly connected components once. The
+/// energized cells of each branching component are kept, the plain chains
+/// between them are walked again on lookup.
+pub struct Contraption {
+    width: usize,
+    height: usize,
+    tiles: Vec<Tile>,
+    /// Component of every state, `cell * 4 + direction`.
+    comp: Vec<usize>,
+    /// Cells of each component, a beam in it lights all of them.
+    cells: Vec<Vec<usize>>,
+    /// Components reached in one step, without the component itself.
+    next: Vec<Vec<usize>>,
+    /// Energized cells as a bitset, for components that loop or don't lead
+    /// to exactly one other.
+    energized: Vec<Option<Vec<u64>>>,
+}
+
+impl From<&str> for Contraption {
+    fn from(input: &str) -> Self {
+        let input = input.trim();
+        let width = input.lines().next().unwrap().len();
+        let height = input.lines().count();
+        let tiles = input
+            .lines()
+            .flat_map(|line| line.chars())
+            .map_into()
+            .collect_vec();
+
+        let mut contraption = Contraption {
+            width,
+            height,
+            tiles,
+            comp: vec![],
+            cells: vec![],
+            next: vec![],
+            energized: vec![],
+        };
+        contraption.condense();
+
+        contraption
+    }
+}
+
+impl Contraption {
+    /// States a beam moves to after entering `state`.
+    fn successors(&self, state: usize) -> impl Iterator<Item = usize> + '_ {
+        let (cell, dir) = (state / 4, Dir::ALL[state % 4]);
+        let (row, col) = (cell / self.width, cell % self.width);
+
+        self.tiles[cell]
+            .interact(dir)
+            .into_vec()
+            .into_iter()
+            .filter_map(move |out| {
+                let (row, col) = match out {
+                    Dir::Up => (row.checked_sub(1)?, col),
+                    Dir::Right => (row, col + 1),
+                    Dir::Down => (row + 1, col),
+                    Dir::Left => (row, col.checked_sub(1)?),
+                };
+
+                (row < self.height && col < self.width)
+                    .then_some((row * self.width + col) * 4 + out as usize)
+            })
+    }
+
+    /// Tarjan's algorithm without recursion, components come out with
+    /// everything they reach already numbered.
+    fn condense(&mut self) {
+        const UNSEEN: usize = usize::MAX;
+
+        let states = self.tiles.len() * 4;
+        let mut index = vec![UNSEEN; states];
+        let mut low = vec![0; states];
+        let mut on_stack = vec![false; states];
+        let mut stack = vec![];
+        let mut comp = vec![UNSEEN; states];
+        let mut counter = 0;
+        let mut comps = 0;
+
+        for root in 0..states {
+            if index[root] != UNSEEN {
+                continue;
+            }
+
+            let mut work = vec![(root, self.successors(root).collect_vec(), 0)];
+            index[root] = counter;
+            low[root] = counter;
+            counter += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((state, next, i)) = work.last_mut() {
+                let state = *state;
+
+                if let Some(&succ) = next.get(*i) {
+                    *i += 1;
+
+                    if index[succ] == UNSEEN {
+                        index[succ] = counter;
+                        low[succ] = counter;
+                        counter += 1;
+                        stack.push(succ);
+                        on_stack[succ] = true;
+                        work.push((succ, self.successors(succ).collect_vec(), 0));
+                    } else if on_stack[succ] {
+                        low[state] = low[state].min(index[succ]);
+                    }
+                    continue;
+                }
+
+                work.pop();
+                if let Some((parent, _, _)) = work.last() {
+                    low[*parent] = low[*parent].min(low[state]);
+                }
+
+                if low[state] == index[state] {
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        comp[member] = comps;
+                        if member == state {
+                            break;
+                        }
+                    }
+                    comps += 1;
+                }
+            }
+        }
+
+        let mut cells = vec![vec![]; comps];
+        let mut next = vec![vec![]; comps];
+
+        for state in 0..states {
+            cells[comp[state]].push(state / 4);
+            next[comp[state]].extend(self.successors(state).map(|s| comp[s]));
+        }
+
+        for (c, (cells, next)) in cells.iter_mut().zip(next.iter_mut()).enumerate() {
+            cells.sort_unstable();
+            cells.dedup();
+            next.sort_unstable();
+            next.dedup();
+            next.retain(|&n| n != c);
+        }
+
+        self.comp = comp;
+        self.cells = cells;
+        self.next = next;
+        self.energized = vec![None; comps];
+
+        // successors always have lower numbers, so they are ready first
+        for c in 0..comps {
+            if self.is_branch(c) {
+                let mut set = self.bitset(&self.cells[c]);
+
+                for &n in self.next[c].iter() {
+                    for (a, b) in set.iter_mut().zip(self.chain(n)) {
+                        *a |= b;
+                    }
+                }
+
+                self.energized[c] = Some(set);
+            }
+        }
+    }
+
+    fn is_branch(&self, c: usize) -> bool {
+        self.cells[c].len() > 1 || self.next[c].len() != 1
+    }
+
+    fn bitset(&self, cells: &[usize]) -> Vec<u64> {
+        let mut set = vec![0; self.tiles.len().div_ceil(64)];
+        for cell in cells {
+            set[cell / 64] |= 1 << (cell % 64);
+        }
+        set
+    }
+
+    /// Energized cells from component `c`, following single steps until a branch.
+    fn chain(&self, mut c: usize) -> Vec<u64> {
+        let mut cells = vec![];
+
+        while !self.is_branch(c) {
+            cells.extend(self.cells[c].iter().copied());
+            c = self.next[c][0];
+        }
+
+        let mut set = self.energized[c].clone().unwrap();
+        for cell in cells {
+            set[cell / 64] |= 1 << (cell % 64);
+        }
+        set
+    }
+
+    /// Beams entering from outside the grid, one per edge cell and side.
+    pub fn entries(&self) -> Vec<(usize, usize, Dir)> {
+        let (last_row, last_col) = (self.height - 1, self.width - 1);
+
+        (0..self.width)
+            .flat_map(|col| [(last_row, col, Dir::Up), (0, col, Dir::Down)])
+            .chain(
+                (0..self.height).flat_map(|row| [(row, 0, Dir::Right), (row, last_col, Dir::Left)]),
+            )
+            .collect_vec()
+    }
+
+    fn lit(&self, (row, col, dir): (usize, usize, Dir)) -> Vec<u64> {
+        self.chain(self.comp[(row * self.width + col) * 4 + dir as usize])
+    }
+
+    /// Cells lit by a beam entering `(row, col)` heading `dir`.
+    pub fn energy(&self, entry: (usize, usize, Dir)) -> usize {
+        self.lit(entry)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// The grid with `#` on energized cells and `.` elsewhere.
+    pub fn render(&self, entry: (usize, usize, Dir)) -> String {
+        let lit = self.lit(entry);
+
+        (0..self.height)
+            .map(|row| {
+                (0..self.width)
+                    .map(|col| row * self.width + col)
+                    .map(|cell| match lit[cell / 64] >> (cell % 64) & 1 {
+                        1 => '#',
+                        _ => '.',
+                    })
+                    .collect::<String>()
+            })
+            .join("\n")
+    }
+}
@@ -1,8 +1,10 @@
 #![feature(test)]
 
-use std::{collections::HashSet, fs};
+mod beams;
 
-use itertools::Itertools;
+use std::fs;
+
+use beams::{Contraption, Dir};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 fn main() {
@@ -10,246 +12,40 @@ fn main() {
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
+
+    if cfg!(feature = "report") {
+        println!(
+            "{}",
+            Contraption::from(input.as_str()).render((0, 0, Dir::Right))
+        );
+    }
 }
 
 fn process_part_1(input: &str) -> String {
-    let (tiles, width, height) = parse(input);
-
-    let beams = tiles.first().unwrap().interact(Dir::Right).into_vec(0, 0);
-
-    get_energy_value(beams, tiles, width, height).to_string()
+    Contraption::from(input)
+        .energy((0, 0, Dir::Right))
+        .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let (tiles, width, height) = parse(input);
-
-    let last_col = width - 1;
-    let last_row = height - 1;
-
-    let up_beams = tiles
-        .iter()
-        .skip(last_row * width)
-        .enumerate()
-        .map(|(col, t)| t.interact(Dir::Up).into_vec(last_row, col))
-        .collect_vec();
+    let contraption = Contraption::from(input);
 
-    let right_beams = tiles
-        .iter()
-        .step_by(width)
-        .enumerate()
-        .map(|(row, t)| t.interact(Dir::Right).into_vec(row, 0))
-        .collect_vec();
-
-    let down_beams = tiles
-        .iter()
-        .take(width)
-        .enumerate()
-        .map(|(col, t)| t.interact(Dir::Down).into_vec(0, col))
-        .collect_vec();
-
-    let left_beams = tiles
-        .iter()
-        .skip(last_col)
-        .step_by(width)
-        .enumerate()
-        .map(|(row, t)| t.interact(Dir::Left).into_vec(row, last_col))
-        .collect_vec();
-
-    let beam_sets = [up_beams, right_beams, down_beams, left_beams].concat();
-
-    beam_sets
+    contraption
+        .entries()
         .into_par_iter()
-        .map(|beams| get_energy_value(beams, tiles.clone(), width, height))
+        .map(|entry| contraption.energy(entry))
         .max()
         .unwrap()
         .to_string()
 }
 
-fn parse(input: &str) -> (Vec<Tile>, usize, usize) {
-    let input = input.trim();
-    let width = input.lines().next().unwrap().len();
-    let height = input.lines().count();
-    let tiles = input
-        .lines()
-        .flat_map(|line| line.chars())
-        .map_into()
-        .collect_vec();
-
-    (tiles, width, height)
-}
-
-fn get_energy_value(
-    mut beams: Vec<(usize, usize, Dir)>,
-    tiles: Vec<Tile>,
-    width: usize,
-    height: usize,
-) -> usize {
-    let mut energized = HashSet::new();
-
-    while let Some((row, col, dir)) = beams.pop() {
-        let next_tile = match dir {
-            Dir::Up => tiles
-                .iter()
-                .enumerate()
-                .skip(col)
-                .step_by(width)
-                .take(row)
-                .rev()
-                .find(|(_, t)| **t != Tile::Empty),
-            Dir::Right => tiles
-                .iter()
-                .enumerate()
-                .skip(row * width + col)
-                .take(width - col)
-                .skip(1)
-                .find(|(_, t)| **t != Tile::Empty),
-            Dir::Down => tiles
-                .iter()
-                .enumerate()
-                .skip(row * width + col)
-                .step_by(width)
-                .take(height - row)
-                .skip(1)
-                .find(|(_, t)| **t != Tile::Empty),
-            Dir::Left => tiles
-                .iter()
-                .enumerate()
-                .skip(row * width)
-                .take(col)
-                .rev()
-                .find(|(_, t)| **t != Tile::Empty),
-        };
-
-        if let Some((ix, tile)) = next_tile {
-            let next_row = ix / width;
-            let next_col = ix % width;
-
-            if !energized.contains(&(row, col, dir)) {
-                match dir {
-                    Dir::Up => (next_row..=row).skip(1).for_each(|i| {
-                        energized.insert((i, col, dir));
-                    }),
-                    Dir::Right => (col..next_col).for_each(|i| {
-                        energized.insert((row, i, dir));
-                    }),
-                    Dir::Down => (row..next_row).for_each(|i| {
-                        energized.insert((i, col, dir));
-                    }),
-                    Dir::Left => (next_col..=col).skip(1).for_each(|i| {
-                        energized.insert((row, i, dir));
-                    }),
-                };
-
-                match tile.interact(dir) {
-                    MaybePair::Single(d) => beams.push((next_row, next_col, d)),
-                    MaybePair::Pair((a, b)) => {
-                        beams.push((next_row, next_col, a));
-                        beams.push((next_row, next_col, b));
-                    }
-                }
-            }
-        } else {
-            match dir {
-                Dir::Up => (0..=row).for_each(|i| {
-                    energized.insert((i, col, dir));
-                }),
-                Dir::Right => (col..width).for_each(|i| {
-                    energized.insert((row, i, dir));
-                }),
-                Dir::Down => (row..height).for_each(|i| {
-                    energized.insert((i, col, dir));
-                }),
-                Dir::Left => (0..=col).for_each(|i| {
-                    energized.insert((row, i, dir));
-                }),
-            };
-        }
-    }
-
-    energized
-        .into_iter()
-        .unique_by(|(row, col, _)| (*row, *col))
-        .count()
-}
-
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
-enum Dir {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-enum MaybePair<T> {
-    Single(T),
-    Pair((T, T)),
-}
-
-impl<Dir> MaybePair<Dir> {
-    fn into_vec(self, row: usize, col: usize) -> Vec<(usize, usize, Dir)> {
-        match self {
-            MaybePair::Single(d) => vec![(row, col, d)],
-            MaybePair::Pair((a, b)) => vec![(row, col, a), (row, col, b)],
-        }
-    }
-}
-
-#[derive(PartialEq, Eq, Clone, Debug)]
-enum Tile {
-    Empty,
-    MirrorUp,
-    MirrorDown,
-    SplitHorz,
-    SplitVert,
-}
-
-impl Tile {
-    fn interact(&self, dir: Dir) -> MaybePair<Dir> {
-        match self {
-            Tile::Empty => MaybePair::Single(dir),
-            Tile::MirrorUp => match dir {
-                Dir::Up => MaybePair::Single(Dir::Right),
-                Dir::Right => MaybePair::Single(Dir::Up),
-                Dir::Down => MaybePair::Single(Dir::Left),
-                Dir::Left => MaybePair::Single(Dir::Down),
-            },
-            Tile::MirrorDown => match dir {
-                Dir::Up => MaybePair::Single(Dir::Left),
-                Dir::Right => MaybePair::Single(Dir::Down),
-                Dir::Down => MaybePair::Single(Dir::Right),
-                Dir::Left => MaybePair::Single(Dir::Up),
-            },
-            Tile::SplitHorz => match dir {
-                Dir::Up | Dir::Down => MaybePair::Pair((Dir::Left, Dir::Right)),
-                Dir::Left | Dir::Right => MaybePair::Single(dir),
-            },
-            Tile::SplitVert => match dir {
-                Dir::Up | Dir::Down => MaybePair::Single(dir),
-                Dir::Left | Dir::Right => MaybePair::Pair((Dir::Up, Dir::Down)),
-            },
-        }
-    }
-}
-
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Tile::Empty,
-            '/' => Tile::MirrorUp,
-            '\\' => Tile::MirrorDown,
-            '-' => Tile::SplitHorz,
-            '|' => Tile::SplitVert,
-            x => panic!("unknown tile: {}", x),
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
 
     use crate::*;
     use rstest::rstest;
+    use std::collections::HashSet;
     use test::Bencher;
 
     const TEST_CASE: &str = r"
@@ -276,6 +72,88 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[test]
+    fn render_check() {
+        let expected = "\
+######....
+.#...#....
+.#...#####
+.#...##...
+.#...##...
+.#...##...
+.#..####..
+########..
+.#######..
+.#...#.#..";
+
+        assert_eq!(
+            expected,
+            Contraption::from(TEST_CASE).render((0, 0, Dir::Right))
+        );
+    }
+
+    /// Beam simulation straight on the grid, one state at a time.
+    fn simulate(input: &str, (row, col, dir): (usize, usize, Dir)) -> usize {
+        let grid = input
+            .trim()
+            .lines()
+            .map(|l| l.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let mut seen = HashSet::new();
+        let mut beams = vec![(row as isize, col as isize, dir)];
+
+        while let Some((r, c, d)) = beams.pop() {
+            let Some(&ch) = grid.get(r as usize).and_then(|l| l.get(c as usize)) else {
+                continue;
+            };
+            if r < 0 || c < 0 || !seen.insert((r, c, d)) {
+                continue;
+            }
+
+            let outs = match (ch, d) {
+                ('/', Dir::Up) | ('\\', Dir::Down) => vec![Dir::Right],
+                ('/', Dir::Right) | ('\\', Dir::Left) => vec![Dir::Up],
+                ('/', Dir::Down) | ('\\', Dir::Up) => vec![Dir::Left],
+                ('/', Dir::Left) | ('\\', Dir::Right) => vec![Dir::Down],
+                ('-', Dir::Up | Dir::Down) => vec![Dir::Left, Dir::Right],
+                ('|', Dir::Left | Dir::Right) => vec![Dir::Up, Dir::Down],
+                _ => vec![d],
+            };
+
+            for out in outs {
+                let (dr, dc) = match out {
+                    Dir::Up => (-1, 0),
+                    Dir::Right => (0, 1),
+                    Dir::Down => (1, 0),
+                    Dir::Left => (0, -1),
+                };
+                beams.push((r + dr, c + dc, out));
+            }
+        }
+
+        seen.iter()
+            .map(|(r, c, _)| (r, c))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[rstest]
+    #[case(TEST_CASE)]
+    #[case("-|..\n|-.\\\n\\./|\n.-|-")]
+    #[case("/-\\.\n|..|\n\\-/.\n....")]
+    fn entries_check(#[case] input: &str) {
+        let contraption = Contraption::from(input);
+
+        for entry in contraption.entries() {
+            assert_eq!(
+                simulate(input, entry),
+                contraption.energy(entry),
+                "{:?}",
+                entry
+            );
+        }
+    }
+
     #[rstest]
     #[case("6361")]
     fn part_1_control(#[case] expected: &str) {
@@ -0,0 +1,115 @@
+use itertools::Itertools;
+
+use super::{direction::Direction, dist_heap::DistHeap, node::Node};
+
+/// Heat loss of every city block, row by row.
+pub struct City {
+    pub width: usize,
+    pub height: usize,
+    pub heat: Vec<u32>,
+}
+
+impl From<&str> for City {
+    fn from(input: &str) -> Self {
+        let input = input.trim();
+        let width = input.lines().next().unwrap().len();
+        let height = input.lines().count();
+        let heat = input.chars().filter_map(|c| c.to_digit(10)).collect_vec();
+
+        City {
+            width,
+            height,
+            heat,
+        }
+    }
+}
+
+impl City {
+    /// The city with the route drawn in arrows over the blocks it enters.
+    pub fn render(&self, route: &Route) -> String {
+        let mut chars = self
+            .heat
+            .iter()
+            .map(|h| char::from_digit(*h, 10).unwrap())
+            .collect_vec();
+
+        for (index, dir) in route.steps.iter() {
+            chars[*index] = dir.arrow();
+        }
+
+        chars
+            .chunks(self.width)
+            .map(|row| row.iter().collect::<String>())
+            .join("\n")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route {
+    pub heat: u32,
+    /// Blocks entered in order, with the direction they were entered in.
+    pub steps: Vec<(usize, Direction)>,
+}
+
+/// Crucible going at least `min_run` and at most `max_run` blocks in a
+/// straight line, it also needs `min_run` blocks behind it to stop.
+pub struct Crucible {
+    min_run: u32,
+    max_run: u32,
+}
+
+impl Crucible {
+    pub fn new(min_run: u32, max_run: u32) -> Result<Self, String> {
+        if max_run == 0 || min_run > max_run {
+            return Err(format!("Invalid runs: {}..={}", min_run, max_run));
+        }
+
+        Ok(Crucible { min_run, max_run })
+    }
+
+    /// Least heat lost from `start` to `goal`, Dijkstra over block,
+    /// direction and straight run.
+    pub fn solve(&self, city: &City, start: usize, goal: usize) -> Option<Route> {
+        if start == goal {
+            return Some(Route {
+                heat: 0,
+                steps: vec![],
+            });
+        }
+
+        let mut heap = DistHeap::new(city.heat.len(), self.max_run, start);
+
+        while let Some(node) = heap.pop() {
+            let Node {
+                index,
+                dist,
+                dir,
+                straight,
+            } = node;
+
+            if index == goal && straight >= self.min_run {
+                let steps = heap
+                    .path(node.state())
+                    .into_iter()
+                    .map(|(index, dir, _)| (index, dir))
+                    .collect_vec();
+
+                return Some(Route { heat: dist, steps });
+            }
+
+            for next_dir in dir.get_next(straight, self.min_run, self.max_run) {
+                if let Some(next_index) = next_dir.get_next_index(index, city.width, city.height) {
+                    let next = Node {
+                        index: next_index,
+                        dist: dist + city.heat[next_index],
+                        dir: next_dir,
+                        straight: if dir == next_dir { straight + 1 } else { 1 },
+                    };
+                    heap.push(next, Some(node.state()));
+                }
+            }
+        }
+
+        None
+    }
+}
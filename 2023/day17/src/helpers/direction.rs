@@ -7,23 +7,17 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn get_next_short(&self, straight: u32) -> Vec<Direction> {
-        let mut res = match self {
-            Direction::Up => vec![Direction::Left, Direction::Right],
-            Direction::Left => vec![Direction::Up, Direction::Down],
-            Direction::Down => vec![Direction::Left, Direction::Right],
-            Direction::Right => vec![Direction::Up, Direction::Down],
-        };
-
-        if straight < 3 {
-            res.push(*self);
-        }
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Left,
+        Direction::Down,
+        Direction::Right,
+    ];
 
-        res
-    }
-
-    pub fn get_next_long(&self, straight: u32) -> Vec<Direction> {
-        let mut res = if straight >= 4 {
+    /// Directions a crucible may take after `straight` blocks this way, it has
+    /// to go at least `min_run` blocks before turning and at most `max_run`.
+    pub fn get_next(&self, straight: u32, min_run: u32, max_run: u32) -> Vec<Direction> {
+        let mut res = if straight >= min_run.max(1) {
             match self {
                 Direction::Up => vec![Direction::Left, Direction::Right],
                 Direction::Left => vec![Direction::Up, Direction::Down],
@@ -34,13 +28,22 @@ impl Direction {
             vec![]
         };
 
-        if straight < 10 {
+        if straight < max_run {
             res.push(*self);
         }
 
         res
     }
 
+    pub fn arrow(&self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Left => '<',
+            Direction::Down => 'v',
+            Direction::Right => '>',
+        }
+    }
+
     pub fn get_next_index(&self, index: usize, width: usize, height: usize) -> Option<usize> {
         match self {
             Direction::Up => {
@@ -51,7 +54,7 @@ impl Direction {
                 }
             }
             Direction::Left => {
-                if (index % width) > 0 {
+                if !index.is_multiple_of(width) {
                     Some(index - 1)
                 } else {
                     None
@@ -1,43 +1,47 @@
 use std::collections::BinaryHeap;
 
-use super::{direction::Direction, node::Node};
+use super::{
+    direction::Direction,
+    node::{Node, State},
+};
 
 pub struct DistHeap {
     heap: BinaryHeap<Node>,
+    /// `dists[index][dir][straight]`, straight runs up to `max_run`.
     dists: Vec<Vec<Vec<u32>>>,
+    /// State each best distance was reached from, `None` at the start.
+    prev: Vec<Vec<Vec<Option<State>>>>,
 }
 
 impl DistHeap {
-    pub fn new(size: usize) -> DistHeap {
+    /// Heap over `size` blocks seeded at `start` facing every way.
+    pub fn new(size: usize, max_run: u32, start: usize) -> DistHeap {
+        let runs = max_run as usize + 1;
         let mut heap = DistHeap {
             heap: BinaryHeap::new(),
-            dists: vec![vec![vec![u32::MAX; 11]; 4]; size],
+            dists: vec![vec![vec![u32::MAX; runs]; 4]; size],
+            prev: vec![vec![vec![None; runs]; 4]; size],
         };
 
-        heap.push(Node {
-            index: 0,
-            dist: 0,
-            dir: Direction::Right,
-            straight: 0,
-        });
-
-        heap.push(Node {
-            index: 0,
-            dist: 0,
-            dir: Direction::Down,
-            straight: 0,
-        });
+        for dir in Direction::ALL {
+            let node = Node {
+                index: start,
+                dist: 0,
+                dir,
+                straight: 0,
+            };
+            heap.push(node, None);
+        }
 
         heap
     }
 
-    pub fn push(&mut self, node: Node) {
-        let dist = self.dists[node.index][node.dir as usize]
-            .get_mut(node.straight as usize)
-            .unwrap();
+    pub fn push(&mut self, node: Node, from: Option<State>) {
+        let (index, dir, straight) = (node.index, node.dir as usize, node.straight as usize);
 
-        if *dist > node.dist {
-            *dist = node.dist;
+        if self.dists[index][dir][straight] > node.dist {
+            self.dists[index][dir][straight] = node.dist;
+            self.prev[index][dir][straight] = from;
             self.heap.push(node);
         }
     }
@@ -45,4 +49,17 @@ impl DistHeap {
     pub fn pop(&mut self) -> Option<Node> {
         self.heap.pop()
     }
+
+    /// States leading from the start up to `state`, the start itself excluded.
+    pub fn path(&self, mut state: State) -> Vec<State> {
+        let mut res = vec![];
+
+        while let Some(prev) = self.prev[state.0][state.1 as usize][state.2 as usize] {
+            res.push(state);
+            state = prev;
+        }
+
+        res.reverse();
+        res
+    }
 }
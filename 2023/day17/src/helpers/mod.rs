@@ -1,3 +1,4 @@
+pub mod crucible;
 pub mod direction;
 pub mod dist_heap;
 pub mod node;
@@ -1,6 +1,6 @@
 use super::direction::Direction;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub struct Node {
     pub index: usize,
     pub dist: u32,
@@ -8,6 +8,16 @@ pub struct Node {
     pub straight: u32,
 }
 
+impl Node {
+    /// Search state, the heat loss left out.
+    pub fn state(&self) -> State {
+        (self.index, self.dir, self.straight)
+    }
+}
+
+/// `(index, direction, blocks straight)` of a crucible.
+pub type State = (usize, Direction, u32);
+
 impl Ord for Node {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         other
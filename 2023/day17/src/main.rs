@@ -4,86 +4,38 @@ mod helpers;
 
 use std::fs;
 
-use itertools::Itertools;
-
-use crate::helpers::{dist_heap::DistHeap, node::Node};
+use crate::helpers::crucible::{City, Crucible, Route};
 
 fn main() {
     let input = fs::read_to_string("./day17/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
-}
 
-fn process_part_1(input: &str) -> String {
-    let (width, height, city) = parse(input);
-    let dest = city.len() - 1;
-    let mut heap = DistHeap::new(city.len());
-
-    while let Some(Node {
-        index,
-        dist,
-        dir,
-        straight,
-    }) = heap.pop()
-    {
-        if index == dest {
-            return dist.to_string();
-        }
+    if cfg!(feature = "report") {
+        let city = City::from(input.as_str());
 
-        for next_dir in dir.get_next_short(straight) {
-            if let Some(next_index) = next_dir.get_next_index(index, width, height) {
-                heap.push(Node {
-                    index: next_index,
-                    dist: dist + city[next_index],
-                    dir: next_dir,
-                    straight: if dir == next_dir { straight + 1 } else { 1 },
-                });
-            }
-        }
+        println!("{}\n", city.render(&process(&input, 1, 3)));
+        println!("{}", city.render(&process(&input, 4, 10)));
     }
+}
 
-    panic!("path not found")
+fn process_part_1(input: &str) -> String {
+    process(input, 1, 3).heat.to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let (width, height, city) = parse(input);
-    let dest = city.len() - 1;
-    let mut heap = DistHeap::new(city.len());
-
-    while let Some(Node {
-        index,
-        dist,
-        dir,
-        straight,
-    }) = heap.pop()
-    {
-        if index == dest && straight >= 4 {
-            return dist.to_string();
-        }
-
-        for next_dir in dir.get_next_long(straight) {
-            if let Some(next_index) = next_dir.get_next_index(index, width, height) {
-                heap.push(Node {
-                    index: next_index,
-                    dist: dist + city[next_index],
-                    dir: next_dir,
-                    straight: if dir == next_dir { straight + 1 } else { 1 },
-                });
-            }
-        }
-    }
-
-    panic!("path not found")
+    process(input, 4, 10).heat.to_string()
 }
 
-fn parse(input: &str) -> (usize, usize, Vec<u32>) {
-    let input = input.trim();
-    let width = input.lines().next().unwrap().len();
-    let height = input.lines().count();
-    let city = input.chars().filter_map(|c| c.to_digit(10)).collect_vec();
+/// Route losing the least heat from the top left to the bottom right block.
+fn process(input: &str, min_run: u32, max_run: u32) -> Route {
+    let city = City::from(input);
 
-    (width, height, city)
+    Crucible::new(min_run, max_run)
+        .unwrap()
+        .solve(&city, 0, city.heat.len() - 1)
+        .expect("path not found")
 }
 
 #[cfg(test)]
@@ -129,6 +81,57 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[test]
+    fn path_check() {
+        let city = City::from(TEST_CASE);
+        let route = process(TEST_CASE, 1, 3);
+
+        let heat: u32 = route.steps.iter().map(|(i, _)| city.heat[*i]).sum();
+        assert_eq!(102, heat);
+        assert_eq!(heat, route.heat);
+        assert_eq!(city.heat.len() - 1, route.steps.last().unwrap().0);
+
+        // never more than three blocks the same way
+        for run in route.steps.chunk_by(|a, b| a.1 == b.1) {
+            assert!(run.len() <= 3);
+        }
+
+        let rendered = city.render(&route);
+        assert_eq!(13, rendered.lines().count());
+        assert_eq!(
+            route.steps.len(),
+            rendered.matches(['>', 'v', '<', '^']).count()
+        );
+    }
+
+    #[rstest]
+    #[case(1, 3, 12, 0, Some(1))]
+    #[case(4, 10, 0, 10, Some(10))]
+    #[case(1, 3, 0, 0, Some(0))]
+    #[case(4, 10, 0, 59, Some(71))]
+    #[case(6, 10, 59, 0, None)]
+    fn start_goal_check(
+        #[case] min_run: u32,
+        #[case] max_run: u32,
+        #[case] start: usize,
+        #[case] goal: usize,
+        #[case] expected: Option<u32>,
+    ) {
+        let city = City::from(TEST_CASE_2);
+        let route = Crucible::new(min_run, max_run)
+            .unwrap()
+            .solve(&city, start, goal);
+
+        assert_eq!(expected, route.map(|r| r.heat));
+    }
+
+    #[test]
+    fn runs_check() {
+        assert!(Crucible::new(0, 0).is_err());
+        assert!(Crucible::new(4, 3).is_err());
+        assert!(Crucible::new(0, 3).is_ok());
+    }
+
     #[rstest]
     #[case("959")]
     fn part_1_control(#[case] expected: &str) {
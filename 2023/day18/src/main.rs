@@ -3,6 +3,8 @@
 use std::fs;
 
 use itertools::Itertools;
+use num::{Integer, Signed};
+use polygon::Polygon;
 
 fn main() {
     let input = fs::read_to_string("./day18/_data/input.txt").expect("oh noes");
@@ -15,11 +17,11 @@ fn process_part_1(input: &str) -> String {
     let parser = |line: &str| {
         line.split_ascii_whitespace()
             .next_tuple()
-            .map(|(d, n)| (d.into(), n.parse::<isize>().unwrap()))
+            .map(|(d, n)| (d.into(), n.parse::<i64>().unwrap()))
             .unwrap()
     };
 
-    process(input, parser).to_string()
+    process::<i64>(input, parser).to_string()
 }
 
 fn process_part_2(input: &str) -> String {
@@ -29,45 +31,32 @@ fn process_part_2(input: &str) -> String {
             .map(|s| {
                 (
                     s[5..6].parse::<u32>().unwrap().into(),
-                    isize::from_str_radix(&s[..5], 16).unwrap(),
+                    i64::from_str_radix(&s[..5], 16).unwrap(),
                 )
             })
             .unwrap()
     };
 
-    process(input, parser).to_string()
+    // hex lengths add up fast, i128 keeps the doubled area far from overflowing
+    process::<i128>(input, parser).to_string()
 }
 
-fn process(input: &str, parser: fn(&str) -> (Direction, isize)) -> isize {
-    let (poly, sum, _, _) = input.trim().lines().map(parser).fold(
-        (vec![], 0isize, 0isize, 0isize),
-        |(mut res, sum, mut row, mut col), (dir, num)| {
-            match dir {
-                Direction::Up => row -= num,
-                Direction::Left => col -= num,
-                Direction::Down => row += num,
-                Direction::Right => col += num,
-            };
-
-            res.push((row, col));
-
-            (res, sum + num, row, col)
-        },
-    );
-
-    // TIL Pick's theorem
-    shoelace(poly).abs() + (sum / 2) + 1
-}
-
-fn shoelace<T: num::Num + num::Signed + num::NumCast + std::clone::Clone>(poly: Vec<(T, T)>) -> T {
-    let poly_size = poly.len();
-
-    poly.into_iter()
-        .cycle()
-        .tuple_windows()
-        .take(poly_size)
-        .fold(T::zero(), |acc, (a, b)| acc + (a.0 * b.1) - (a.1 * b.0))
-        .div(T::from(2).unwrap())
+/// Cells of the trench dug along the plan together with everything it encloses.
+fn process<T: Signed + Integer + Copy + From<i64>>(
+    input: &str,
+    parser: fn(&str) -> (Direction, i64),
+) -> T {
+    Polygon::<T>::from_moves(input.trim().lines().map(parser).map(|(dir, num)| {
+        let num = T::from(num);
+
+        match dir {
+            Direction::Up => (T::zero(), -num),
+            Direction::Left => (-num, T::zero()),
+            Direction::Down => (T::zero(), num),
+            Direction::Right => (num, T::zero()),
+        }
+    }))
+    .lattice_points()
 }
 
 #[derive(Debug)]
@@ -1,5 +1,4 @@
-pub mod category;
 pub mod operation;
 pub mod part;
-pub mod range_part;
+pub mod tree;
 pub mod workflow;
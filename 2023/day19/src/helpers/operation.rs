@@ -1,31 +1,32 @@
 use itertools::Itertools;
 
-use super::category::Category;
-
+/// Rule of a workflow, comparisons name their category as written.
 pub enum Operation {
-    LesserThan(Category, u32, String),
-    GreaterThan(Category, u32, String),
+    LesserThan(String, u64, String),
+    GreaterThan(String, u64, String),
     Always(String),
 }
 
-impl From<&str> for Operation {
-    fn from(value: &str) -> Self {
-        if let Some((op, label)) = value.split(':').collect_tuple() {
-            match &op[1..2] {
-                ">" => Operation::GreaterThan(
-                    op[..1].into(),
-                    op[2..].parse().unwrap(),
-                    label.to_owned(),
-                ),
-                "<" => Operation::LesserThan(
-                    op[..1].into(),
-                    op[2..].parse().unwrap(),
-                    label.to_owned(),
-                ),
-                x => panic!("unknown operation '{}' in '{}'", x, op),
-            }
-        } else {
-            Operation::Always(value.to_owned())
-        }
+impl TryFrom<&str> for Operation {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let Some((op, label)) = value.split(':').collect_tuple() else {
+            return Ok(Operation::Always(value.to_owned()));
+        };
+
+        let i = op
+            .find(['<', '>'])
+            .filter(|&i| i > 0)
+            .ok_or_else(|| format!("Unknown operation: {}", op))?;
+        let category = op[..i].to_owned();
+        let threshold = op[i + 1..]
+            .parse()
+            .map_err(|_| format!("Invalid threshold: {}", op))?;
+
+        Ok(match &op[i..i + 1] {
+            "<" => Operation::LesserThan(category, threshold, label.to_owned()),
+            _ => Operation::GreaterThan(category, threshold, label.to_owned()),
+        })
     }
 }
@@ -1,104 +1,42 @@
 use itertools::Itertools;
 
-use super::{category::Category, operation::Operation};
-
+/// Ratings by category name, in the order they were written.
 pub struct Part {
-    pub x: u32,
-    pub m: u32,
-    pub a: u32,
-    pub s: u32,
+    pub ratings: Vec<(String, u64)>,
 }
 
 impl Part {
-    pub fn get_rating(self) -> u32 {
-        self.x + self.m + self.a + self.s
+    pub fn get_rating(self) -> u64 {
+        self.ratings.into_iter().map(|(_, v)| v).sum()
     }
 
-    pub fn apply_op(&self, op: &Operation) -> Option<String> {
-        match op {
-            Operation::LesserThan(c, v, l) => {
-                let l = l.to_owned();
-                match c {
-                    Category::X => {
-                        if self.x < *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::M => {
-                        if self.m < *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::A => {
-                        if self.a < *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::S => {
-                        if self.s < *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                }
-            }
-            Operation::GreaterThan(c, v, l) => {
-                let l = l.to_owned();
-                match c {
-                    Category::X => {
-                        if self.x > *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::M => {
-                        if self.m > *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::A => {
-                        if self.a > *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                    Category::S => {
-                        if self.s > *v {
-                            Some(l)
-                        } else {
-                            None
-                        }
-                    }
-                }
-            }
-            Operation::Always(l) => Some(l.to_owned()),
+    /// Ratings ordered as `categories`, every one of them has to be rated.
+    pub fn values<const N: usize>(&self, categories: [&str; N]) -> Result<[u64; N], String> {
+        let mut res = [0; N];
+
+        for (value, category) in res.iter_mut().zip(categories) {
+            *value = self
+                .ratings
+                .iter()
+                .find(|(c, _)| c == category)
+                .map(|(_, v)| *v)
+                .ok_or_else(|| format!("Part has no {} rating", category))?;
         }
+
+        Ok(res)
     }
 }
 
 impl From<&str> for Part {
     fn from(value: &str) -> Self {
-        let (x, m, a, s) = value[1..(value.len() - 1)]
+        let ratings = value[1..(value.len() - 1)]
             .split(',')
-            .collect_tuple()
-            .unwrap();
+            .map(|rating| {
+                let (category, value) = rating.split('=').collect_tuple().unwrap();
+                (category.to_owned(), value.parse::<u64>().unwrap())
+            })
+            .collect_vec();
 
-        Part {
-            x: x[2..].parse::<u32>().unwrap(),
-            m: m[2..].parse::<u32>().unwrap(),
-            a: a[2..].parse::<u32>().unwrap(),
-            s: s[2..].parse::<u32>().unwrap(),
-        }
+        Part { ratings }
     }
 }
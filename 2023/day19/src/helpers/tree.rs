@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use rangeset::RangeBox;
+
+use super::{operation::Operation, workflow::Workflow};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Accept,
+    Reject,
+    /// Values of `axis` below `at` go down `below`, the others down `above`.
+    Branch {
+        axis: usize,
+        at: u64,
+        below: Box<Node>,
+        above: Box<Node>,
+    },
+}
+
+impl Node {
+    /// Branch node, unless both sides end up the same and the comparison is pointless.
+    fn branch(axis: usize, at: u64, below: Node, above: Node) -> Node {
+        if below == above {
+            return below;
+        }
+
+        Node::Branch {
+            axis,
+            at,
+            below: Box::new(below),
+            above: Box::new(above),
+        }
+    }
+}
+
+/// Workflows compiled into a binary decision tree over `N` categories.
+/// Comparisons that can't go both ways for the ratings reaching them are
+/// dropped, so every branch left really splits the parts.
+#[derive(Debug, Clone)]
+pub struct Tree<const N: usize> {
+    root: Node,
+    domain: RangeBox<u64, N>,
+    unreachable_rules: Vec<(String, usize)>,
+    dead_workflows: Vec<String>,
+}
+
+impl<const N: usize> Tree<N> {
+    /// Compiles from the `in` workflow for ratings inside `domain`,
+    /// `categories[axis]` naming each axis of the domain.
+    pub fn compile(
+        workflows: &[Workflow],
+        categories: [&str; N],
+        domain: RangeBox<u64, N>,
+    ) -> Result<Self, String> {
+        let mut compiler = Compiler {
+            workflows: workflows
+                .iter()
+                .map(|wf| (wf.label.as_str(), wf.ops.as_slice()))
+                .collect(),
+            categories,
+            reached: HashSet::new(),
+            path: vec![],
+        };
+
+        let root = if domain.is_empty() {
+            Node::Reject
+        } else {
+            compiler.workflow("in", domain.clone())?
+        };
+
+        let unreachable_rules = workflows
+            .iter()
+            .filter(|wf| compiler.reached.contains(&(wf.label.as_str(), 0)))
+            .flat_map(|wf| (0..wf.ops.len()).map(move |idx| (wf.label.as_str(), idx)))
+            .filter(|rule| !compiler.reached.contains(rule))
+            .map(|(label, idx)| (label.to_owned(), idx))
+            .collect();
+
+        let dead_workflows = workflows
+            .iter()
+            .filter(|wf| !compiler.reached.contains(&(wf.label.as_str(), 0)))
+            .map(|wf| wf.label.clone())
+            .collect();
+
+        Ok(Tree {
+            root,
+            domain,
+            unreachable_rules,
+            dead_workflows,
+        })
+    }
+
+    pub fn evaluate(&self, ratings: &[u64; N]) -> bool {
+        if !self.domain.contains(ratings) {
+            return false;
+        }
+
+        let mut node = &self.root;
+
+        loop {
+            match node {
+                Node::Accept => return true,
+                Node::Reject => return false,
+                Node::Branch {
+                    axis,
+                    at,
+                    below,
+                    above,
+                } => node = if ratings[*axis] < *at { below } else { above },
+            }
+        }
+    }
+
+    /// Disjoint boxes of accepted ratings, one per accepting leaf.
+    pub fn accepted(&self) -> Vec<RangeBox<u64, N>> {
+        let mut res = vec![];
+        let mut stack = vec![(&self.root, self.domain.clone())];
+
+        while let Some((node, range)) = stack.pop() {
+            match node {
+                Node::Accept => res.push(range),
+                Node::Reject => {}
+                Node::Branch {
+                    axis,
+                    at,
+                    below,
+                    above,
+                } => {
+                    let (b, a) = range.split_at(*axis, *at);
+                    stack.extend(b.map(|b| (below.as_ref(), b)));
+                    stack.extend(a.map(|a| (above.as_ref(), a)));
+                }
+            }
+        }
+
+        res
+    }
+
+    /// `(workflow, rule index)` of rules no rating ever gets to,
+    /// earlier rules of a live workflow catch everything first.
+    pub fn unreachable_rules(&self) -> &[(String, usize)] {
+        &self.unreachable_rules
+    }
+
+    /// Workflows never entered from `in`.
+    pub fn dead_workflows(&self) -> &[String] {
+        &self.dead_workflows
+    }
+}
+
+struct Compiler<'a, const N: usize> {
+    workflows: HashMap<&'a str, &'a [Operation]>,
+    categories: [&'a str; N],
+    /// `(workflow, rule index)` reached by a non-empty box.
+    reached: HashSet<(&'a str, usize)>,
+    /// Workflows being compiled, a label showing up twice is a cycle.
+    path: Vec<&'a str>,
+}
+
+impl<'a, const N: usize> Compiler<'a, N> {
+    fn workflow(&mut self, label: &'a str, range: RangeBox<u64, N>) -> Result<Node, String> {
+        match label {
+            "A" => return Ok(Node::Accept),
+            "R" => return Ok(Node::Reject),
+            _ => {}
+        }
+
+        if !self.workflows.contains_key(label) {
+            return Err(format!("Unknown workflow: {}", label));
+        }
+
+        if self.path.contains(&label) {
+            return Err(format!("Workflow cycle through: {}", label));
+        }
+
+        self.path.push(label);
+        let node = self.rules(label, 0, range);
+        self.path.pop();
+
+        node
+    }
+
+    fn rules(
+        &mut self,
+        label: &'a str,
+        idx: usize,
+        range: RangeBox<u64, N>,
+    ) -> Result<Node, String> {
+        let Some(op) = self.workflows[label].get(idx) else {
+            return Err(format!("Workflow {} has no fallback rule", label));
+        };
+
+        self.reached.insert((label, idx));
+
+        let (axis, at, target, below_matches) = match op {
+            Operation::Always(target) => return self.workflow(target, range),
+            Operation::LesserThan(cat, val, target) => (self.axis(cat)?, *val, target, true),
+            Operation::GreaterThan(cat, val, target) => (self.axis(cat)?, *val + 1, target, false),
+        };
+
+        let (below, above) = range.split_at(axis, at);
+        let (matched, rest) = if below_matches {
+            (below, above)
+        } else {
+            (above, below)
+        };
+
+        let matched = matched.map(|r| self.workflow(target, r)).transpose()?;
+        let rest = rest.map(|r| self.rules(label, idx + 1, r)).transpose()?;
+
+        Ok(match (matched, rest) {
+            (Some(node), None) | (None, Some(node)) => node,
+            (Some(matched), Some(rest)) if below_matches => Node::branch(axis, at, matched, rest),
+            (Some(matched), Some(rest)) => Node::branch(axis, at, rest, matched),
+            (None, None) => unreachable!("split of a non-empty box"),
+        })
+    }
+
+    fn axis(&self, category: &str) -> Result<usize, String> {
+        self.categories
+            .iter()
+            .position(|c| *c == category)
+            .ok_or_else(|| format!("Unknown category: {}", category))
+    }
+}
@@ -7,14 +7,22 @@ pub struct Workflow {
     pub ops: Vec<Operation>,
 }
 
-impl From<&str> for Workflow {
-    fn from(value: &str) -> Self {
-        let (label, rest) = value.split('{').collect_tuple().unwrap();
-        let ops = rest[..(rest.len() - 1)].split(',').map_into().collect_vec();
+impl TryFrom<&str> for Workflow {
+    type Error = String;
 
-        Workflow {
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (label, rest) = value
+            .strip_suffix('}')
+            .and_then(|v| v.split('{').collect_tuple())
+            .ok_or_else(|| format!("Invalid workflow: {}", value))?;
+        let ops = rest
+            .split(',')
+            .map(Operation::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Workflow {
             label: label.to_owned(),
             ops,
-        }
+        })
     }
 }
@@ -2,88 +2,66 @@
 
 mod helpers;
 
-use std::{collections::HashMap, fs};
+use std::fs;
 
-use helpers::{part::Part, range_part::RangePart, workflow::Workflow};
+use helpers::{part::Part, tree::Tree, workflow::Workflow};
 use itertools::Itertools;
+use rangeset::RangeBox;
+
+const CATEGORIES: [&str; 4] = ["x", "m", "a", "s"];
 
 fn main() {
     let input = fs::read_to_string("./day19/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
+
+    if cfg!(feature = "report") {
+        report(&input);
+    }
+}
+
+/// Analysis of the compiled workflows, printed with the `report` feature.
+fn report(input: &str) {
+    let (wfs, _) = parse(input).unwrap();
+    let tree = compile(&wfs);
+
+    println!("Unreachable rules: {:?}", tree.unreachable_rules());
+    println!("Dead workflows: {:?}", tree.dead_workflows());
+    println!("Accepted boxes: {}", tree.accepted().len());
 }
 
 fn process_part_1(input: &str) -> String {
-    let (wfs, parts) = parse(input);
-    let wfs = HashMap::<_, _>::from_iter(wfs.into_iter().map(|wf| (wf.label, wf.ops)));
+    let (wfs, parts) = parse(input).unwrap();
+    let tree = compile(&wfs);
 
     parts
         .into_iter()
-        .filter(|part| {
-            let mut wf = wfs.get("in").unwrap();
-            loop {
-                for op in wf {
-                    if let Some(res) = part.apply_op(op) {
-                        match res.as_str() {
-                            "A" => return true,
-                            "R" => return false,
-                            x => {
-                                wf = wfs.get(x).unwrap();
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        })
+        .filter(|part| tree.evaluate(&part.values(CATEGORIES).unwrap()))
         .map(Part::get_rating)
-        .sum::<u32>()
+        .sum::<u64>()
         .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let (wfs, _) = parse(input);
-    let wfs = HashMap::<_, _>::from_iter(wfs.into_iter().map(|wf| (wf.label, wf.ops)));
-
-    let mut ranges = vec![(
-        RangePart {
-            x: (1, 4000),
-            m: (1, 4000),
-            a: (1, 4000),
-            s: (1, 4000),
-        },
-        "in".to_owned(),
-    )];
-
-    let mut sum = 0usize;
-
-    while let Some((range, label)) = ranges.pop() {
-        let wf = wfs.get(&label).unwrap();
-        let mut range = Some(range);
-
-        for op in wf {
-            if let Some(r) = range {
-                let (matched, unmatched) = r.apply_op(op);
-                range = unmatched;
-
-                if let Some(matched) = matched {
-                    match matched.1.as_str() {
-                        "A" => sum += matched.0.get_combinations(),
-                        "R" => {}
-                        _ => ranges.push(matched),
-                    }
-                }
-            } else {
-                break;
-            }
-        }
-    }
+    let (wfs, _) = parse(input).unwrap();
 
-    sum.to_string()
+    compile(&wfs)
+        .accepted()
+        .iter()
+        .map(RangeBox::volume)
+        .sum::<u64>()
+        .to_string()
+}
+
+/// Tree over every `x`, `m`, `a` and `s` rating from 1 to 4000.
+fn compile(wfs: &[Workflow]) -> Tree<4> {
+    let domain = RangeBox::new(std::array::from_fn(|_| 1..4001));
+
+    Tree::compile(wfs, CATEGORIES, domain).unwrap()
 }
 
-fn parse(input: &str) -> (Vec<Workflow>, Vec<Part>) {
+fn parse(input: &str) -> Result<(Vec<Workflow>, Vec<Part>), String> {
     let (wfs, _, parts) = input
         .trim()
         .lines()
@@ -93,10 +71,12 @@ fn parse(input: &str) -> (Vec<Workflow>, Vec<Part>) {
         .collect_tuple()
         .unwrap();
 
-    (
-        wfs.into_iter().map_into().collect_vec(),
+    Ok((
+        wfs.into_iter()
+            .map(Workflow::try_from)
+            .collect::<Result<_, _>>()?,
         parts.into_iter().map_into().collect_vec(),
-    )
+    ))
 }
 
 #[cfg(test)]
@@ -138,6 +118,87 @@ hdj{m>838:A,pv}
         assert_eq!(expected, process_part_2(input));
     }
 
+    const TEST_CASE_DEAD: &str = "
+in{x<100:low,x>99:R,A}
+low{x>200:A,a<5:R,m<1:A,A}
+dead{s<1:A,R}
+
+{x=10,m=1,a=5,s=1}";
+
+    #[test]
+    fn analysis_check() {
+        let tree = compile(&parse(TEST_CASE_DEAD).unwrap().0);
+
+        assert_eq!(&[("in".to_owned(), 2)], tree.unreachable_rules());
+        assert_eq!(&["dead".to_owned()], tree.dead_workflows());
+        assert_eq!(
+            vec![RangeBox::new([1..100, 1..4001, 5..4001, 1..4001])],
+            tree.accepted()
+        );
+
+        assert!(tree.evaluate(&[10, 1, 5, 1]));
+        assert!(!tree.evaluate(&[10, 1, 4, 1]));
+        assert!(!tree.evaluate(&[100, 1, 5, 1]));
+        assert!(!tree.evaluate(&[10, 1, 5, 0]));
+    }
+
+    #[test]
+    fn simplify_check() {
+        let tree = compile(
+            &parse("in{a<5:A,s>10:lnx,A}\nlnx{m>1548:A,A}\n\n{x=1,m=1,a=1,s=1}")
+                .unwrap()
+                .0,
+        );
+
+        assert_eq!(1, tree.accepted().len());
+        assert!(tree.unreachable_rules().is_empty());
+    }
+
+    #[rstest]
+    #[case("in{x<5:A,bad}", "Unknown workflow: bad")]
+    #[case("in{q<5:A,R}", "Unknown category: q")]
+    #[case("in{x<5:A,a}\na{m>3:in,R}", "Workflow cycle through: in")]
+    #[case("in{x<5:A}", "Workflow in has no fallback rule")]
+    fn compile_error_check(#[case] workflows: &str, #[case] expected: &str) {
+        let wfs = workflows
+            .lines()
+            .map(|wf| Workflow::try_from(wf).unwrap())
+            .collect_vec();
+        let domain = RangeBox::new(std::array::from_fn(|_| 1..4001));
+
+        assert_eq!(
+            Some(expected.to_owned()),
+            Tree::compile(&wfs, CATEGORIES, domain).err()
+        );
+    }
+
+    #[rstest]
+    #[case("in{x=5:A,R}", "Unknown operation: x=5")]
+    #[case("in{<5:A,R}", "Unknown operation: <5")]
+    #[case("in{x<five:A,R}", "Invalid threshold: x<five")]
+    #[case("in{x<5:A,R", "Invalid workflow: in{x<5:A,R")]
+    fn parse_error_check(#[case] workflow: &str, #[case] expected: &str) {
+        assert_eq!(
+            Some(expected.to_owned()),
+            Workflow::try_from(workflow).err()
+        );
+    }
+
+    #[test]
+    fn category_names_check() {
+        let wfs =
+            ["in{xx>5:low,été<3:A,R}", "low{xx<2:R,A}"].map(|wf| Workflow::try_from(wf).unwrap());
+        let domain = RangeBox::new([1..11, 1..11]);
+        let tree = Tree::compile(&wfs, ["xx", "été"], domain).unwrap();
+
+        assert_eq!(
+            vec![RangeBox::new([6..11, 1..11]), RangeBox::new([1..6, 1..3])],
+            tree.accepted()
+        );
+        assert!(tree.evaluate(&[6, 10]));
+        assert!(!tree.evaluate(&[5, 3]));
+    }
+
     #[rstest]
     #[case("368964")]
     fn part_1_control(#[case] expected: &str) {
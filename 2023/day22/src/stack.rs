@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+/// Brick spanning `from` to `to` inclusive, `from` holding the smaller coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Brick {
+    pub from: (u32, u32, u32),
+    pub to: (u32, u32, u32),
+}
+
+impl From<&str> for Brick {
+    fn from(line: &str) -> Self {
+        let (a, b) = line
+            .split(['~', ','])
+            .map(|p| p.parse::<u32>().unwrap())
+            .tuples::<(_, _, _)>()
+            .next_tuple()
+            .unwrap();
+
+        Brick {
+            from: (a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            to: (a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        }
+    }
+}
+
+impl Brick {
+    fn columns(&self) -> impl Iterator<Item = (u32, u32)> {
+        let (from, to) = (self.from, self.to);
+
+        (from.0..=to.0).cartesian_product(from.1..=to.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Axis {
+    X,
+    Y,
+}
+
+/// Settled bricks, in input order, with the support DAG rooted at the
+/// ground and its dominator tree. A brick dominates every brick that only
+/// stands on the ground through it, which are exactly the bricks falling
+/// once it is removed.
+pub struct Stack {
+    bricks: Vec<Brick>,
+    /// `below[id]` bricks `id` rests on, empty on the ground.
+    below: Vec<Vec<usize>>,
+    /// Immediate dominator, `bricks.len()` standing for the ground.
+    idom: Vec<usize>,
+    /// Size of the dominator subtree, the brick itself included.
+    subtree: Vec<usize>,
+}
+
+impl From<&str> for Stack {
+    fn from(input: &str) -> Self {
+        let mut bricks = input.trim().lines().map(Brick::from).collect_vec();
+        let ground = bricks.len();
+
+        let order = (0..bricks.len())
+            .sorted_by_key(|&id| bricks[id].from.2)
+            .collect_vec();
+
+        // highest settled cube of every column and the brick it belongs to
+        let mut heights = HashMap::<(u32, u32), (u32, usize)>::new();
+        let mut below = vec![vec![]; bricks.len()];
+
+        for &id in order.iter() {
+            let top = bricks[id]
+                .columns()
+                .filter_map(|col| heights.get(&col))
+                .map(|&(z, _)| z)
+                .max()
+                .unwrap_or(0);
+
+            below[id] = bricks[id]
+                .columns()
+                .filter_map(|col| heights.get(&col))
+                .filter(|&&(z, _)| top > 0 && z == top)
+                .map(|&(_, other)| other)
+                .unique()
+                .collect_vec();
+
+            let brick = &mut bricks[id];
+            let drop = brick.from.2 - top - 1;
+            brick.from.2 -= drop;
+            brick.to.2 -= drop;
+
+            for col in brick.columns() {
+                heights.insert(col, (brick.to.2, id));
+            }
+        }
+
+        // supporters settle first, so their dominators are known by then
+        let mut idom = vec![ground; bricks.len() + 1];
+        let mut depth = vec![0; bricks.len() + 1];
+
+        for &id in order.iter() {
+            let parent = below[id]
+                .iter()
+                .copied()
+                .reduce(|a, b| common_dominator(&idom, &depth, a, b))
+                .unwrap_or(ground);
+
+            idom[id] = parent;
+            depth[id] = depth[parent] + 1;
+        }
+
+        let mut subtree = vec![1; bricks.len() + 1];
+
+        for &id in order.iter().rev() {
+            subtree[idom[id]] += subtree[id];
+        }
+
+        idom.pop();
+        subtree.pop();
+
+        Stack {
+            bricks,
+            below,
+            idom,
+            subtree,
+        }
+    }
+}
+
+/// Deepest common ancestor of `a` and `b` in the dominator tree built so far.
+fn common_dominator(idom: &[usize], depth: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        if depth[a] < depth[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        a = idom[a];
+    }
+
+    a
+}
+
+impl Stack {
+    pub fn bricks(&self) -> &[Brick] {
+        &self.bricks
+    }
+
+    /// Bricks `id` directly rests on.
+    pub fn supporters(&self, id: usize) -> &[usize] {
+        &self.below[id]
+    }
+
+    /// Closest brick whose removal brings `id` down, `None` if only the ground holds it.
+    pub fn dominator(&self, id: usize) -> Option<usize> {
+        Some(self.idom[id]).filter(|&d| d < self.bricks.len())
+    }
+
+    /// Number of other bricks falling when `id` is disintegrated.
+    pub fn chain_reaction(&self, id: usize) -> usize {
+        self.subtree[id] - 1
+    }
+
+    /// Stack seen along `axis`, one line per layer from the top down to the
+    /// ground, `?` where several bricks hide behind each other.
+    pub fn side_view(&self, axis: Axis) -> String {
+        let span = |b: &Brick| match axis {
+            Axis::X => b.from.0..=b.to.0,
+            Axis::Y => b.from.1..=b.to.1,
+        };
+
+        let width = self
+            .bricks
+            .iter()
+            .map(|b| *span(b).end())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let height = self.bricks.iter().map(|b| b.to.2).max().unwrap_or(0);
+        let mut cells = vec![vec![vec![]; width as usize]; height as usize + 1];
+
+        for (id, brick) in self.bricks.iter().enumerate() {
+            for z in brick.from.2..=brick.to.2 {
+                for col in span(brick) {
+                    cells[z as usize][col as usize].push(id);
+                }
+            }
+        }
+
+        let mut lines = (1..=height)
+            .rev()
+            .map(|z| {
+                let row = cells[z as usize]
+                    .iter()
+                    .map(|ids| match ids.as_slice() {
+                        [] => '.',
+                        [id] => (b'A' + (id % 26) as u8) as char,
+                        _ => '?',
+                    })
+                    .collect::<String>();
+
+                format!("{} {}", row, z)
+            })
+            .collect_vec();
+        lines.push(format!("{} 0", "-".repeat(width as usize)));
+
+        lines.join("\n")
+    }
+}
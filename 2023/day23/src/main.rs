@@ -1,12 +1,11 @@
 #![feature(test)]
 
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+mod trails;
+
+use std::fs;
 
 use itertools::Itertools;
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use trails::{Graph, Tile};
 
 fn main() {
     let input = fs::read_to_string("./day23/_data/input.txt").expect("oh noes");
@@ -16,254 +15,28 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    let field = input
-        .trim()
-        .lines()
-        .map(|line| line.chars().map_into().collect_vec())
-        .collect_vec();
-
-    let mut graph = HashMap::new();
-    fill_slippery_graph(PRESTART_POINT, START_POINT, &mut graph, &field);
-
-    get_longest(graph).to_string()
+    longest(input, true)
 }
 
 fn process_part_2(input: &str) -> String {
-    let field = input
-        .trim()
-        .lines()
-        .map(|line| line.chars().map_into().collect_vec())
-        .collect_vec();
-
-    let mut graph = HashMap::new();
-    fill_dry_graph(PRESTART_POINT, START_POINT, &mut graph, &field);
-
-    get_longest(graph).to_string()
+    longest(input, false)
 }
 
-const START_POINT: (isize, isize) = (0, 1);
-const PRESTART_POINT: (isize, isize) = (-1, 1);
-const START_ID: isize = get_id(PRESTART_POINT);
-const END_ID: isize = 999_999_999_999;
-
-fn fill_slippery_graph(
-    mut prev_pos: (isize, isize),
-    (mut row, mut col): (isize, isize),
-    graph: &mut HashMap<isize, HashSet<(usize, isize)>>,
-    field: &[Vec<Tile>],
-) {
-    let start = get_id(prev_pos);
-
-    graph.entry(start).or_default();
-
-    let mut len = 0;
-
-    loop {
-        len += 1;
-
-        let down = (row + 1, col);
-
-        if get_from_field(down, field).is_none() {
-            graph.entry(start).and_modify(|v| {
-                v.insert((len, END_ID));
-            });
-            return;
-        }
-
-        let up = ((row - 1, col), Tile::SlopeUp);
-        let right = ((row, col + 1), Tile::SlopeRight);
-        let down = (down, Tile::SlopeDown);
-        let left = ((row, col - 1), Tile::SlopeLeft);
-
-        let valid_conts = [up, right, down, left]
-            .into_iter()
-            .filter_map(|(pos, slope)| {
-                if pos == prev_pos {
-                    None
-                } else {
-                    let tile = get_from_field(pos, field).unwrap();
-
-                    if tile == Tile::Path || tile == slope {
-                        Some(pos)
-                    } else {
-                        None
-                    }
-                }
-            })
-            .collect_vec();
-
-        prev_pos = (row, col);
-
-        if valid_conts.len() == 1 {
-            (row, col) = *valid_conts.first().unwrap();
-            continue;
-        }
-
-        let end = get_id(prev_pos);
-
-        graph.entry(start).and_modify(|v| {
-            v.insert((len, end));
-        });
-
-        if graph.contains_key(&end) {
-            return;
-        }
-
-        for cont in valid_conts {
-            fill_slippery_graph(prev_pos, cont, graph, field);
-        }
-
-        return;
-    }
+fn longest(input: &str, slippery: bool) -> String {
+    Graph::new(&parse(input), slippery)
+        .unwrap()
+        .with_perimeter_rule()
+        .longest_path()
+        .unwrap()
+        .to_string()
 }
 
-fn fill_dry_graph(
-    mut prev_pos: (isize, isize),
-    (mut row, mut col): (isize, isize),
-    graph: &mut HashMap<isize, HashSet<(usize, isize)>>,
-    field: &[Vec<Tile>],
-) {
-    let start = get_id(prev_pos);
-
-    graph.entry(start).or_default();
-
-    let mut len = 0;
-
-    loop {
-        len += 1;
-
-        let down = (row + 1, col);
-
-        if get_from_field(down, field).is_none() {
-            graph.entry(start).and_modify(|v| {
-                v.insert((len, END_ID));
-            });
-            return;
-        }
-
-        let up = (row - 1, col);
-        let right = (row, col + 1);
-        let left = (row, col - 1);
-
-        let valid_conts = [up, right, down, left]
-            .into_iter()
-            .filter(|pos| *pos != prev_pos && get_from_field(*pos, field).unwrap() != Tile::Forest)
-            .collect_vec();
-
-        prev_pos = (row, col);
-
-        if valid_conts.len() == 1 {
-            (row, col) = *valid_conts.first().unwrap();
-            continue;
-        }
-
-        let end = get_id(prev_pos);
-        let end_processed = graph.contains_key(&end);
-
-        graph.entry(start).and_modify(|v| {
-            v.insert((len, end));
-        });
-
-        let end_set = graph.entry(end).or_default();
-        end_set.insert((len, start));
-
-        if end_processed {
-            return;
-        }
-
-        for cont in valid_conts {
-            fill_dry_graph(prev_pos, cont, graph, field);
-        }
-
-        return;
-    }
-}
-
-const fn get_id((row, col): (isize, isize)) -> isize {
-    row * 100_000 + col
-}
-
-fn get_from_field((row, col): (isize, isize), field: &[Vec<Tile>]) -> Option<Tile> {
-    if row < 0 || col < 0 {
-        None
-    } else {
-        field
-            .get(row as usize)
-            .and_then(|r| r.get(col as usize))
-            .copied()
-    }
-}
-
-fn get_longest(graph: HashMap<isize, HashSet<(usize, isize)>>) -> usize {
-    get_path_from_graph(START_ID, HashSet::new(), &graph).unwrap() - 1
-}
-
-fn get_path_from_graph(
-    current: isize,
-    mut visited: HashSet<isize>,
-    graph: &HashMap<isize, HashSet<(usize, isize)>>,
-) -> Option<usize> {
-    visited.insert(current);
-
-    let mut targets = Vec::new();
-    let mut res = Vec::new();
-
-    for (len, target) in graph.get(&current).unwrap() {
-        if *target == END_ID {
-            res.push(*len);
-        } else if !visited.contains(target) {
-            targets.push((*len, *target));
-        }
-    }
-
-    if !targets.is_empty() {
-        if targets.len() > 1 {
-            if let Some(max) = targets
-                .into_par_iter()
-                .filter_map(|(len, target)| {
-                    get_path_from_graph(target, visited.clone(), graph).map(|l| l + len)
-                })
-                .max()
-            {
-                res.push(max);
-            }
-        } else {
-            let (len, target) = targets.first().unwrap();
-            if let Some(max) = get_path_from_graph(*target, visited, graph) {
-                res.push(max + len);
-            }
-        }
-    }
-
-    if res.is_empty() {
-        None
-    } else {
-        res.into_iter().max()
-    }
-}
-
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
-enum Tile {
-    Path,
-    Forest,
-    SlopeUp,
-    SlopeRight,
-    SlopeDown,
-    SlopeLeft,
-}
-
-impl From<char> for Tile {
-    fn from(value: char) -> Self {
-        match value {
-            '.' => Tile::Path,
-            '#' => Tile::Forest,
-            '^' => Tile::SlopeUp,
-            '>' => Tile::SlopeRight,
-            'v' => Tile::SlopeDown,
-            '<' => Tile::SlopeLeft,
-            x => panic!("unknown tile: {x}"),
-        }
-    }
+fn parse(input: &str) -> Vec<Vec<Tile>> {
+    input
+        .trim()
+        .lines()
+        .map(|line| line.chars().map_into().collect_vec())
+        .collect_vec()
 }
 
 #[cfg(test)]
@@ -311,6 +84,82 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    /// Square grid of `size` by `size` junctions, every corridor
+    /// `len` tiles long, entered at the top left and left at the bottom right.
+    fn grid_maze(size: usize, len: usize) -> String {
+        let side = (size - 1) * (len + 1) + 3;
+        let mut rows = vec![vec!['#'; side]; side];
+
+        let lines = (0..size).map(|i| 1 + i * (len + 1)).collect_vec();
+
+        for (r, row) in rows.iter_mut().enumerate().take(side - 1).skip(1) {
+            for (c, cell) in row.iter_mut().enumerate().take(side - 1).skip(1) {
+                if lines.contains(&r) || lines.contains(&c) {
+                    *cell = '.';
+                }
+            }
+        }
+        rows[0][1] = '.';
+        rows[side - 1][side - 2] = '.';
+
+        rows.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .join("\n")
+    }
+
+    /// Plain exhaustive search, no pruning and no perimeter rule.
+    fn brute(graph: &Graph, start: (usize, usize), end: (usize, usize)) -> Option<usize> {
+        let edges = graph.edges().collect_vec();
+        let mut best = None;
+        let mut stack = vec![(start, vec![start], 0)];
+
+        while let Some((node, path, len)) = stack.pop() {
+            if node == end {
+                best = best.max(Some(len));
+                continue;
+            }
+
+            for &(_, to, l) in edges.iter().filter(|(from, _, _)| *from == node) {
+                if !path.contains(&to) {
+                    let mut path = path.clone();
+                    path.push(to);
+                    stack.push((to, path, len + l));
+                }
+            }
+        }
+
+        best
+    }
+
+    #[rstest]
+    #[case(2, 1)]
+    #[case(3, 2)]
+    #[case(4, 1)]
+    fn longest_path_check(#[case] size: usize, #[case] len: usize) {
+        let field = parse(&grid_maze(size, len));
+        let side = field.len();
+        let graph = Graph::new(&field, false).unwrap();
+        let expected = brute(&graph, (0, 1), (side - 1, side - 2));
+
+        let pruned = graph.clone().with_perimeter_rule();
+
+        assert!(pruned.edges().count() < graph.edges().count());
+        assert_eq!(expected, graph.longest_path());
+        assert_eq!(expected, pruned.longest_path());
+    }
+
+    #[test]
+    fn junctions_check() {
+        let graph = Graph::new(&parse(TEST_CASE), true).unwrap();
+
+        assert_eq!(9, graph.len());
+        assert_eq!(
+            Some(((0, 1), (5, 3), 15)),
+            graph.edges().find(|(from, _, _)| *from == (0, 1))
+        );
+        assert!(Graph::new(&parse(&grid_maze(9, 1)), false).is_err());
+    }
+
     #[rstest]
     #[case("2190")]
     fn part_1_control(#[case] expected: &str) {
@@ -0,0 +1,273 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Tile {
+    Path,
+    Forest,
+    SlopeUp,
+    SlopeRight,
+    SlopeDown,
+    SlopeLeft,
+}
+
+impl From<char> for Tile {
+    fn from(value: char) -> Self {
+        match value {
+            '.' => Tile::Path,
+            '#' => Tile::Forest,
+            '^' => Tile::SlopeUp,
+            '>' => Tile::SlopeRight,
+            'v' => Tile::SlopeDown,
+            '<' => Tile::SlopeLeft,
+            x => panic!("unknown tile: {x}"),
+        }
+    }
+}
+
+const MOVES: [((isize, isize), Tile); 4] = [
+    ((-1, 0), Tile::SlopeUp),
+    ((0, 1), Tile::SlopeRight),
+    ((1, 0), Tile::SlopeDown),
+    ((0, -1), Tile::SlopeLeft),
+];
+
+/// Partial paths searched one after the other, the rest is split over threads.
+const FAN_OUT_DEPTH: usize = 6;
+
+/// `(node, visited, length, best entries of the unvisited nodes)`.
+type State = (usize, u64, usize, usize);
+
+/// Junctions of the maze, with the start and exit, joined by the corridors
+/// between them. At most 64 nodes so a visited set fits in a `u64`.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    nodes: Vec<(usize, usize)>,
+    /// `edges[from]` as `(to, length)`, the longest corridor when several join two nodes.
+    edges: Vec<Vec<(usize, usize)>>,
+    start: usize,
+    end: usize,
+}
+
+impl Graph {
+    /// Contracts `field` from the open tile of the first row to the one of the last,
+    /// `slippery` slopes only being walked downhill.
+    pub fn new(field: &[Vec<Tile>], slippery: bool) -> Result<Self, String> {
+        let open_in = |row: usize| {
+            field
+                .get(row)
+                .and_then(|line| line.iter().position(|t| *t != Tile::Forest))
+                .map(|col| (row, col))
+                .ok_or_else(|| format!("No opening in row {}", row))
+        };
+        let start = open_in(0)?;
+        let end = open_in(field.len().saturating_sub(1))?;
+
+        let tile = |(row, col): (usize, usize)| field[row][col];
+        let steps = |(row, col): (usize, usize)| {
+            MOVES.into_iter().filter_map(move |((dr, dc), slope)| {
+                let next = (row.checked_add_signed(dr)?, col.checked_add_signed(dc)?);
+                let target = *field.get(next.0)?.get(next.1)?;
+                let walkable = |t: Tile| !slippery || t == Tile::Path || t == slope;
+
+                (target != Tile::Forest && walkable(target) && walkable(tile((row, col))))
+                    .then_some(next)
+            })
+        };
+        let open_neighbours = |(row, col): (usize, usize)| {
+            MOVES
+                .iter()
+                .filter_map(|((dr, dc), _)| {
+                    let next = (row.checked_add_signed(*dr)?, col.checked_add_signed(*dc)?);
+                    field
+                        .get(next.0)?
+                        .get(next.1)
+                        .filter(|t| **t != Tile::Forest)
+                })
+                .count()
+        };
+
+        let nodes = [start, end]
+            .into_iter()
+            .chain((0..field.len()).flat_map(|row| {
+                (0..field[row].len())
+                    .map(move |col| (row, col))
+                    .filter(|&pos| tile(pos) != Tile::Forest && open_neighbours(pos) > 2)
+            }))
+            .unique()
+            .collect_vec();
+
+        if nodes.len() > 64 {
+            return Err(format!("{} junctions don't fit a u64 mask", nodes.len()));
+        }
+
+        let ids = HashMap::<_, _>::from_iter(nodes.iter().enumerate().map(|(id, &p)| (p, id)));
+        let mut edges = vec![vec![]; nodes.len()];
+
+        for (from, &pos) in nodes.iter().enumerate() {
+            for first in steps(pos) {
+                let (mut prev, mut cur, mut len) = (pos, first, 1);
+
+                while !ids.contains_key(&cur) {
+                    let Some(next) = steps(cur).find(|&next| next != prev) else {
+                        break;
+                    };
+                    (prev, cur, len) = (cur, next, len + 1);
+                }
+
+                if let Some(&to) = ids.get(&cur).filter(|&&to| to != from) {
+                    match edges[from].iter_mut().find(|(t, _)| *t == to) {
+                        Some((_, l)) => *l = len.max(*l),
+                        None => edges[from].push((to, len)),
+                    }
+                }
+            }
+        }
+
+        Ok(Graph {
+            nodes,
+            edges,
+            start: ids[&start],
+            end: ids[&end],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Corridors as `(from, to, length)` between junction positions.
+    #[cfg(test)]
+    pub fn edges(&self) -> impl Iterator<Item = ((usize, usize), (usize, usize), usize)> + '_ {
+        self.edges.iter().enumerate().flat_map(move |(from, out)| {
+            out.iter()
+                .map(move |&(to, len)| (self.nodes[from], self.nodes[to], len))
+        })
+    }
+
+    /// On a grid of junctions the ones with fewer than four corridors lie on
+    /// the outer ring, and a path walking the ring away from the exit cuts
+    /// itself off. Those corridors are dropped so the ring only leads towards
+    /// the exit. Graphs that aren't such a ring are left untouched.
+    pub fn with_perimeter_rule(mut self) -> Self {
+        let neighbours = (0..self.len())
+            .map(|node| {
+                (0..self.len())
+                    .filter(|&other| {
+                        self.edges[node].iter().any(|(to, _)| *to == other)
+                            || self.edges[other].iter().any(|(to, _)| *to == node)
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let ring = neighbours.iter().map(|n| n.len() < 4).collect_vec();
+        let ring_neighbours = |node: usize| neighbours[node].iter().filter(|&&n| ring[n]).count();
+
+        // every ring junction is on one of the two sides joining the corners next
+        // to the start and the exit, so it has exactly two ring neighbours
+        let corners = [self.start, self.end].map(|node| neighbours[node].first().copied());
+        let is_ring = (0..self.len())
+            .filter(|&node| ring[node] && node != self.start && node != self.end)
+            .filter(|&node| !corners.contains(&Some(node)))
+            .all(|node| ring_neighbours(node) == 2);
+
+        if !is_ring || corners.contains(&None) {
+            return self;
+        }
+
+        let mut dist = vec![usize::MAX; self.len()];
+        let mut queue = VecDeque::from([self.end]);
+        dist[self.end] = 0;
+
+        while let Some(node) = queue.pop_front() {
+            for &next in neighbours[node].iter().filter(|&&n| ring[n]) {
+                if dist[next] == usize::MAX {
+                    dist[next] = dist[node] + 1;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for (from, out) in self.edges.iter_mut().enumerate() {
+            if ring[from] {
+                out.retain(|&(to, _)| !ring[to] || dist[to] < dist[from]);
+            }
+        }
+
+        self
+    }
+
+    /// Longest path from start to exit never visiting a node twice.
+    pub fn longest_path(&self) -> Option<usize> {
+        let mut best_in = vec![0; self.len()];
+        for (to, len) in self.edges.iter().flatten() {
+            best_in[*to] = best_in[*to].max(*len);
+        }
+
+        // once at the only way into the exit, going anywhere else can't come back
+        let into_end = (0..self.len())
+            .filter(|&node| self.edges[node].iter().any(|(to, _)| *to == self.end))
+            .collect_vec();
+        let gate = (into_end.len() == 1).then(|| into_end[0]);
+
+        let best = AtomicUsize::new(0);
+        let remaining = best_in.iter().sum::<usize>() - best_in[self.start];
+        let mut frontier: Vec<State> = vec![(self.start, 1 << self.start, 0, remaining)];
+
+        for _ in 0..FAN_OUT_DEPTH {
+            frontier = frontier
+                .into_iter()
+                .flat_map(|state| {
+                    if state.0 == self.end {
+                        vec![state]
+                    } else {
+                        self.successors(state, gate, &best_in).collect_vec()
+                    }
+                })
+                .collect();
+        }
+
+        frontier
+            .into_par_iter()
+            .for_each(|state| self.search(state, gate, &best_in, &best));
+
+        Some(best.into_inner()).filter(|&len| len > 0)
+    }
+
+    fn search(&self, state: State, gate: Option<usize>, best_in: &[usize], best: &AtomicUsize) {
+        let (node, _, len, remaining) = state;
+
+        if node == self.end {
+            best.fetch_max(len, Ordering::Relaxed);
+            return;
+        }
+
+        // every unvisited node is entered at most once, through its longest edge at best
+        if len + remaining <= best.load(Ordering::Relaxed) {
+            return;
+        }
+
+        for next in self.successors(state, gate, best_in) {
+            self.search(next, gate, best_in, best);
+        }
+    }
+
+    fn successors<'a>(
+        &'a self,
+        (node, visited, len, remaining): State,
+        gate: Option<usize>,
+        best_in: &'a [usize],
+    ) -> impl Iterator<Item = State> + 'a {
+        self.edges[node]
+            .iter()
+            .filter(move |(to, _)| gate != Some(node) || *to == self.end)
+            .filter(move |(to, _)| visited & (1 << to) == 0)
+            .map(move |&(to, l)| (to, visited | (1 << to), len + l, remaining - best_in[to]))
+    }
+}
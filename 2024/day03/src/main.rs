@@ -89,6 +89,7 @@ mod tests {
 
     use crate::*;
     use rstest::{fixture, rstest};
+    use stress::{generate, Rng, StdRng};
     use test::Bencher;
 
     const TEST_CASE: &str =
@@ -126,6 +127,80 @@ mod tests {
         assert_eq!(expected, process_part_2(&input));
     }
 
+    stress::register! {
+        generator: generate_memory,
+        sizes: [1, 5, 25, 125],
+        part_1_stress: reference_part_1 => process_part_1,
+        part_2_stress: reference_part_2 => process_part_2,
+    }
+
+    /// Corrupted memory of `size` fragments, valid instructions mixed with near misses.
+    fn generate_memory(rng: &mut StdRng, size: usize) -> String {
+        let noise = [
+            "%", "&", "!", "@", "^", "+", "then(", "what()", "from()", "'", "]", " ",
+        ];
+        let broken = [
+            "mul[3,7]",
+            "mul(4*",
+            "mul(32,64]",
+            "mul ( 2 , 4 )",
+            "do_not_",
+            "don't",
+        ];
+
+        (0..size)
+            .map(
+                |_| match generate::weighted(rng, &[(0, 4), (1, 1), (2, 1), (3, 3), (4, 2)]) {
+                    0 => format!("mul({},{})", rng.gen_range(0..1000), rng.gen_range(0..1000)),
+                    1 => "do()".to_owned(),
+                    2 => "don't()".to_owned(),
+                    3 => noise[rng.gen_range(0..noise.len())].to_owned(),
+                    _ => broken[rng.gen_range(0..broken.len())].to_owned(),
+                },
+            )
+            .collect()
+    }
+
+    /// Products of all well formed `mul(a,b)`, optionally honoring `do()`/`don't()`.
+    fn reference_scan(input: &str, conditional: bool) -> String {
+        let mut sum = 0;
+        let mut enabled = true;
+
+        for i in 0..input.len() {
+            let rest = &input[i..];
+
+            if rest.starts_with("do()") {
+                enabled = true;
+            } else if rest.starts_with("don't()") {
+                enabled = !conditional;
+            } else if let Some(args) = rest.strip_prefix("mul(") {
+                let Some((args, _)) = args.split_once(')') else {
+                    continue;
+                };
+                let Some((a, b)) = args.split_once(',') else {
+                    continue;
+                };
+
+                let is_num =
+                    |s: &str| (1..=3).contains(&s.len()) && s.chars().all(|c| c.is_ascii_digit());
+
+                if enabled && is_num(a) && is_num(b) {
+                    sum += a.parse::<i32>().unwrap() * b.parse::<i32>().unwrap();
+                }
+            }
+        }
+
+        sum.to_string()
+    }
+
+    fn reference_part_1(input: &str) -> String {
+        reference_scan(input, false)
+    }
+
+    fn reference_part_2(input: &str) -> String {
+        reference_scan(input, true)
+    }
+
     #[bench]
     fn part_1_check_bench(b: &mut Bencher) {
         b.iter(|| process_part_1(TEST_CASE));
@@ -1,9 +1,11 @@
 #![feature(test)]
 
-use std::{
-    collections::{HashMap, HashSet},
-    fs,
-};
+mod patrol;
+
+use std::fs;
+
+use itertools::Itertools;
+use patrol::Lab;
 
 fn main() {
     let input = fs::read_to_string("./day06/_data/input.txt").expect("oh noes");
@@ -13,194 +15,24 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    let Data {
-        mut guard_pos,
-        obstacles,
-        max_size,
-    } = parse(input);
-
-    let mut direction = Direction::Up;
-    let mut visited = HashSet::from([guard_pos]);
-
-    while let Some(next_pos) = try_get_next_pos(guard_pos, &direction, max_size) {
-        if obstacles.contains(&next_pos) {
-            direction = turn_right(&direction)
-        } else {
-            visited.insert(next_pos);
-            guard_pos = next_pos;
-        }
-    }
-
-    visited.len().to_string()
+    let patrol = Lab::try_from(input).unwrap().patrol(None);
+    assert!(!patrol.looped, "the guard never leaves the lab");
+
+    patrol
+        .steps
+        .iter()
+        .map(|(pos, _)| pos)
+        .unique()
+        .count()
+        .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let Data {
-        mut guard_pos,
-        obstacles,
-        max_size,
-    } = parse(input);
-
-    let mut direction = Direction::Up;
-    let mut visited = HashMap::from([(guard_pos, HashSet::from([direction]))]);
-    let mut obstructions = HashSet::new();
-
-    while let Some(next_pos) = try_get_next_pos(guard_pos, &direction, max_size) {
-        if obstacles.contains(&next_pos) {
-            direction = turn_right(&direction);
-            visited
-                .entry(guard_pos)
-                .and_modify(|v| {
-                    v.insert(direction);
-                })
-                .or_insert_with(|| HashSet::from([direction]));
-        } else {
-            if !visited.contains_key(&next_pos)
-                && is_valid_obstruction(
-                    next_pos, direction, guard_pos, max_size, &visited, &obstacles,
-                )
-            {
-                obstructions.insert(next_pos);
-            }
-
-            visited
-                .entry(next_pos)
-                .and_modify(|v| {
-                    v.insert(direction);
-                })
-                .or_insert_with(|| HashSet::from([direction]));
-            guard_pos = next_pos;
-        }
-    }
-
-    obstructions.len().to_string()
-}
-
-fn is_valid_obstruction(
-    next_pos: (usize, usize),
-    direction: Direction,
-    guard_pos: (usize, usize),
-    max_size: (usize, usize),
-    visited: &HashMap<(usize, usize), HashSet<Direction>>,
-    obstacles: &HashSet<(usize, usize)>,
-) -> bool {
-    let mut alt_dir = turn_right(&direction);
-    let mut alt_pos = guard_pos;
-    let mut alt_visited = HashSet::from([(alt_pos, alt_dir)]);
-
-    while let Some(next_alt_pos) = try_get_next_pos(alt_pos, &alt_dir, max_size) {
-        if obstacles.contains(&next_alt_pos) || next_pos == next_alt_pos {
-            alt_dir = turn_right(&alt_dir);
-            alt_visited.insert((alt_pos, alt_dir));
-        } else {
-            let next_alt_dir_pos = (next_alt_pos, alt_dir);
-
-            if alt_visited.contains(&next_alt_dir_pos)
-                || visited
-                    .get(&next_alt_pos)
-                    .is_some_and(|v| v.contains(&alt_dir))
-            {
-                return true;
-            }
-
-            alt_visited.insert(next_alt_dir_pos);
-            alt_pos = next_alt_pos;
-        }
-    }
-
-    false
-}
-
-fn try_get_next_pos(
-    pos: (usize, usize),
-    direction: &Direction,
-    max_size: (usize, usize),
-) -> Option<(usize, usize)> {
-    let (pos_row, pos_col) = pos;
-    let (max_row, max_col) = max_size;
-    match direction {
-        Direction::Up => {
-            if pos_row == 0 {
-                None
-            } else {
-                Some((pos_row - 1, pos_col))
-            }
-        }
-        Direction::Down => {
-            if pos_row == max_row {
-                None
-            } else {
-                Some((pos_row + 1, pos_col))
-            }
-        }
-        Direction::Left => {
-            if pos_col == 0 {
-                None
-            } else {
-                Some((pos_row, pos_col - 1))
-            }
-        }
-        Direction::Right => {
-            if pos_col == max_col {
-                None
-            } else {
-                Some((pos_row, pos_col + 1))
-            }
-        }
-    }
-}
-
-fn turn_right(direction: &Direction) -> Direction {
-    match direction {
-        Direction::Up => Direction::Right,
-        Direction::Down => Direction::Left,
-        Direction::Left => Direction::Up,
-        Direction::Right => Direction::Down,
-    }
-}
-
-#[derive(Hash, PartialEq, Eq, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-struct Data {
-    guard_pos: (usize, usize),
-    obstacles: HashSet<(usize, usize)>,
-    max_size: (usize, usize),
-}
-
-fn parse(input: &str) -> Data {
-    let mut guard_pos = None;
-    let mut obstacles = HashSet::new();
-    let mut max_row = 0;
-    let mut max_col = 0;
-
-    for (row, line) in input.lines().filter(|line| !line.is_empty()).enumerate() {
-        if max_col == 0 {
-            max_col = line.len() - 1;
-        }
-        if max_row < row {
-            max_row = row;
-        }
-
-        for (col, ch) in line.char_indices() {
-            if ch == '^' {
-                guard_pos = Some((row, col));
-            } else if ch == '#' {
-                obstacles.insert((row, col));
-            }
-        }
-    }
-
-    Data {
-        guard_pos: guard_pos.unwrap(),
-        obstacles,
-        max_size: (max_row, max_col),
-    }
+    Lab::try_from(input)
+        .unwrap()
+        .loop_obstructions()
+        .len()
+        .to_string()
 }
 
 #[cfg(test)]
@@ -208,7 +40,10 @@ mod tests {
     extern crate test;
 
     use crate::*;
+    use patrol::Direction;
     use rstest::{fixture, rstest};
+    use std::collections::HashSet;
+    use stress::{generate, StdRng};
     use test::Bencher;
 
     const TEST_CASE: &str = "
@@ -236,10 +71,46 @@ mod tests {
 
     #[rstest]
     #[case(TEST_CASE, "6")]
+    #[case(".#...\n..#^#\n...#.\n.....\n.#...", "1")]
     fn part_2_check(#[case] input: &str, #[case] expected: &str) {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[test]
+    fn render_check() {
+        let expected = "
+....#.....
+....XXXXX#
+....X...X.
+..#.X...X.
+..XXXXX#X.
+..X.X.X.X.
+.#XXXXXXX.
+.XXXXXXX#.
+#XXXXXXX..
+......#X..";
+
+        let lab = Lab::try_from(TEST_CASE).unwrap();
+
+        assert_eq!(expected.trim_start(), lab.render(None));
+    }
+
+    #[rstest]
+    #[case((6, 3))]
+    #[case((7, 6))]
+    #[case((7, 7))]
+    #[case((8, 1))]
+    #[case((8, 3))]
+    #[case((9, 7))]
+    fn loop_check(#[case] extra: (usize, usize)) {
+        let lab = Lab::try_from(TEST_CASE).unwrap();
+        let patrol = lab.patrol(Some(extra));
+
+        assert!(lab.loops_with(extra));
+        assert!(patrol.looped);
+        assert_eq!(Some(&((6, 4), Direction::Up)), patrol.steps.first());
+    }
+
     #[rstest]
     #[case("4656")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -252,6 +123,74 @@ mod tests {
         assert_eq!(expected, process_part_2(&input));
     }
 
+    stress::register! {
+        generator: generate_lab,
+        sizes: [2, 5, 10, 20],
+        part_1_stress: reference_part_1 => process_part_1,
+        part_2_stress: reference_part_2 => process_part_2,
+    }
+
+    /// Random lab the guard eventually leaves, as the puzzle guarantees.
+    fn generate_lab(rng: &mut StdRng, size: usize) -> String {
+        loop {
+            let mut grid = generate::grid(rng, size, size, &[('.', 8), ('#', 1)]);
+
+            if generate::place(rng, &mut grid, '.', '^').is_none() {
+                grid[0][0] = '^';
+            }
+
+            if walk(&grid, None).is_some() {
+                return generate::render_grid(&grid);
+            }
+        }
+    }
+
+    /// Distinct cells visited before leaving, `None` if the guard loops.
+    fn walk(grid: &[Vec<char>], extra: Option<(isize, isize)>) -> Option<usize> {
+        let (rows, cols) = (grid.len() as isize, grid[0].len() as isize);
+        let mut pos = (0..rows)
+            .cartesian_product(0..cols)
+            .find(|&(r, c)| grid[r as usize][c as usize] == '^')
+            .unwrap();
+        let mut dir = (-1, 0);
+        let mut seen = HashSet::from([(pos, dir)]);
+
+        loop {
+            let next = (pos.0 + dir.0, pos.1 + dir.1);
+
+            if next.0 < 0 || next.1 < 0 || next.0 >= rows || next.1 >= cols {
+                return Some(seen.iter().map(|(p, _)| p).unique().count());
+            }
+
+            if grid[next.0 as usize][next.1 as usize] == '#' || Some(next) == extra {
+                dir = (dir.1, -dir.0);
+            } else {
+                pos = next;
+            }
+
+            if !seen.insert((pos, dir)) {
+                return None;
+            }
+        }
+    }
+
+    fn reference_part_1(input: &str) -> String {
+        let grid = input.lines().map(|l| l.chars().collect_vec()).collect_vec();
+
+        walk(&grid, None).unwrap().to_string()
+    }
+
+    fn reference_part_2(input: &str) -> String {
+        let grid = input.lines().map(|l| l.chars().collect_vec()).collect_vec();
+
+        (0..grid.len() as isize)
+            .cartesian_product(0..grid[0].len() as isize)
+            .filter(|&(r, c)| grid[r as usize][c as usize] == '.')
+            .filter(|&pos| walk(&grid, Some(pos)).is_none())
+            .count()
+            .to_string()
+    }
+
     #[bench]
     fn part_1_check_bench(b: &mut Bencher) {
         b.iter(|| process_part_1(TEST_CASE));
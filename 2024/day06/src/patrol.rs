@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(self) -> Direction {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Patrol {
+    /// Every cell the guard stands on, in order, with the direction faced on arrival.
+    pub steps: Vec<((usize, usize), Direction)>,
+    pub looped: bool,
+}
+
+pub struct Lab {
+    rows: usize,
+    cols: usize,
+    obstacles: Vec<Vec<bool>>,
+    guard: (usize, usize),
+    /// `free[dir][row][col]` is how many steps the guard can take from the cell
+    /// before bumping into an obstacle, `None` if it walks off the map instead.
+    free: [Vec<Vec<Option<usize>>>; 4],
+}
+
+impl TryFrom<&str> for Lab {
+    type Error = String;
+
+    fn try_from(input: &str) -> Result<Self, Self::Error> {
+        let lines = input.lines().filter(|line| !line.is_empty()).collect_vec();
+        let cols = lines.first().map(|l| l.len()).ok_or("Empty lab")?;
+
+        if let Some(line) = lines.iter().find(|l| l.len() != cols) {
+            return Err(format!("Ragged row: {}", line));
+        }
+
+        let guard = lines
+            .iter()
+            .enumerate()
+            .find_map(|(row, line)| line.find('^').map(|col| (row, col)))
+            .ok_or("No guard")?;
+
+        let obstacles = lines
+            .iter()
+            .map(|line| line.chars().map(|ch| ch == '#').collect_vec())
+            .collect_vec();
+
+        let mut lab = Lab {
+            rows: lines.len(),
+            cols,
+            obstacles,
+            guard,
+            free: Default::default(),
+        };
+
+        lab.free = Direction::ALL.map(|dir| lab.build_free(dir));
+
+        Ok(lab)
+    }
+}
+
+impl Lab {
+    /// Walks the guard cell by cell until it leaves the lab or repeats itself.
+    pub fn patrol(&self, extra: Option<(usize, usize)>) -> Patrol {
+        let mut pos = self.guard;
+        let mut dir = Direction::Up;
+        let mut seen = HashSet::from([(pos, dir)]);
+        let mut steps = vec![(pos, dir)];
+
+        loop {
+            let Some(next) = self.next_pos(pos, dir, 1) else {
+                return Patrol {
+                    steps,
+                    looped: false,
+                };
+            };
+
+            if self.obstacles[next.0][next.1] || Some(next) == extra {
+                dir = dir.turn_right();
+            } else {
+                pos = next;
+                steps.push((pos, dir));
+            }
+
+            if !seen.insert((pos, dir)) {
+                return Patrol {
+                    steps,
+                    looped: true,
+                };
+            }
+        }
+    }
+
+    /// Whether one more obstacle at `extra` traps the guard,
+    /// following the jump tables so only turns are visited.
+    pub fn loops_with(&self, extra: (usize, usize)) -> bool {
+        let mut pos = self.guard;
+        let mut dir = Direction::Up;
+        let mut turns = HashSet::new();
+
+        loop {
+            let free = self.free[dir as usize][pos.0][pos.1];
+            let blocked = self
+                .distance_to(pos, dir, extra)
+                .filter(|&dist| free.is_none_or(|free| dist <= free));
+
+            let steps = match (blocked, free) {
+                (Some(dist), _) => dist - 1,
+                (None, Some(free)) => free,
+                (None, None) => return false,
+            };
+
+            pos = self.next_pos(pos, dir, steps).unwrap();
+            dir = dir.turn_right();
+
+            if !turns.insert((pos, dir)) {
+                return true;
+            }
+        }
+    }
+
+    /// Cells where a single new obstacle makes the guard loop,
+    /// only cells on the original route can change anything.
+    pub fn loop_obstructions(&self) -> Vec<(usize, usize)> {
+        self.patrol(None)
+            .steps
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .filter(|&pos| pos != self.guard)
+            .unique()
+            .collect_vec()
+            .into_par_iter()
+            .filter(|&pos| self.loops_with(pos))
+            .collect()
+    }
+
+    /// Lab map with the patrol route marked `X` and the extra obstacle as `O`.
+    #[cfg(test)]
+    pub fn render(&self, extra: Option<(usize, usize)>) -> String {
+        let mut grid = self
+            .obstacles
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&blocked| if blocked { '#' } else { '.' })
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        for ((row, col), _) in self.patrol(extra).steps {
+            grid[row][col] = 'X';
+        }
+
+        if let Some((row, col)) = extra {
+            grid[row][col] = 'O';
+        }
+
+        grid.iter()
+            .map(|row| row.iter().collect::<String>())
+            .join("\n")
+    }
+
+    fn build_free(&self, dir: Direction) -> Vec<Vec<Option<usize>>> {
+        let mut free = vec![vec![None; self.cols]; self.rows];
+
+        // sweep against the walking direction so the cell ahead is always known
+        let order = (0..self.rows)
+            .cartesian_product(0..self.cols)
+            .sorted_by_key(|&(row, col)| {
+                let (dr, dc) = dir.delta();
+                -(dr * row as isize + dc * col as isize)
+            })
+            .collect_vec();
+
+        for (row, col) in order {
+            free[row][col] = match self.next_pos((row, col), dir, 1) {
+                None => None,
+                Some((r, c)) if self.obstacles[r][c] => Some(0),
+                Some((r, c)) => free[r][c].map(|steps| steps + 1),
+            };
+        }
+
+        free
+    }
+
+    /// Steps from `pos` to `target` along `dir`, if it lies straight ahead.
+    fn distance_to(
+        &self,
+        pos: (usize, usize),
+        dir: Direction,
+        target: (usize, usize),
+    ) -> Option<usize> {
+        let (dr, dc) = dir.delta();
+        let row_diff = target.0 as isize - pos.0 as isize;
+        let col_diff = target.1 as isize - pos.1 as isize;
+
+        match (dr, dc) {
+            (0, _) if row_diff == 0 && col_diff * dc > 0 => Some(col_diff.unsigned_abs()),
+            (_, 0) if col_diff == 0 && row_diff * dr > 0 => Some(row_diff.unsigned_abs()),
+            _ => None,
+        }
+    }
+
+    fn next_pos(
+        &self,
+        pos: (usize, usize),
+        dir: Direction,
+        steps: usize,
+    ) -> Option<(usize, usize)> {
+        let (dr, dc) = dir.delta();
+        let row = pos.0.checked_add_signed(dr * steps as isize)?;
+        let col = pos.1.checked_add_signed(dc * steps as isize)?;
+
+        (row < self.rows && col < self.cols).then_some((row, col))
+    }
+}
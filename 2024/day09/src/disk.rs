@@ -0,0 +1,167 @@
+use std::{cmp::Reverse, collections::BinaryHeap, fmt};
+
+use itertools::Itertools;
+
+#[derive(Debug, Clone, Copy)]
+struct File {
+    id: usize,
+    pos: usize,
+    len: usize,
+}
+
+/// Disk in run-length form, files sorted by position and free space implied
+/// by the gaps between them.
+#[derive(Debug, Clone)]
+pub struct Disk {
+    files: Vec<File>,
+    size: usize,
+}
+
+impl From<&str> for Disk {
+    fn from(input: &str) -> Self {
+        let mut files = vec![];
+        let mut pos = 0;
+
+        for (i, len) in input
+            .trim()
+            .chars()
+            .filter_map(|ch| ch.to_digit(10).map(|n| n as usize))
+            .enumerate()
+        {
+            if i % 2 == 0 && len > 0 {
+                files.push(File {
+                    id: i / 2,
+                    pos,
+                    len,
+                });
+            }
+            pos += len;
+        }
+
+        Disk { files, size: pos }
+    }
+}
+
+impl fmt::Display for Disk {
+    /// Block view as in the puzzle, ids past 9 are printed in brackets.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut pos = 0;
+
+        for file in self.files.iter() {
+            write!(f, "{}", ".".repeat(file.pos - pos))?;
+
+            let block = if file.id < 10 {
+                file.id.to_string()
+            } else {
+                format!("[{}]", file.id)
+            };
+            write!(f, "{}", block.repeat(file.len))?;
+
+            pos = file.pos + file.len;
+        }
+
+        write!(f, "{}", ".".repeat(self.size - pos))
+    }
+}
+
+impl Disk {
+    pub fn checksum(&self) -> usize {
+        self.files
+            .iter()
+            .map(|f| f.id * (f.pos * f.len + f.len * (f.len - 1) / 2))
+            .sum()
+    }
+
+    /// Moves single blocks from the end into the leftmost free space,
+    /// splitting files across gaps as needed.
+    pub fn compact_blocks(&self) -> Disk {
+        let mut files = self.files.clone();
+        let mut moved = vec![];
+        let mut back = files.len();
+
+        for (mut pos, mut len) in self.gaps() {
+            while len > 0 && back > 0 {
+                let file = &mut files[back - 1];
+
+                if file.pos + file.len - 1 <= pos {
+                    break;
+                }
+
+                let take = len.min(file.len);
+                moved.push(File {
+                    id: file.id,
+                    pos,
+                    len: take,
+                });
+
+                pos += take;
+                len -= take;
+                file.len -= take;
+
+                if file.len == 0 {
+                    back -= 1;
+                }
+            }
+        }
+
+        files.truncate(back);
+        files.extend(moved);
+        files.sort_unstable_by_key(|f| f.pos);
+
+        Disk {
+            files,
+            size: self.size,
+        }
+    }
+
+    /// Moves whole files, highest id first, into the leftmost gap that fits.
+    /// Gaps are kept in one min-heap of positions per gap size, 1 to 9 for puzzle input.
+    pub fn compact_files(&self) -> Disk {
+        let gaps = self.gaps();
+        let max_gap = gaps.iter().map(|&(_, len)| len).max().unwrap_or(0);
+        let mut spans = vec![BinaryHeap::new(); max_gap + 1];
+        let mut files = self.files.clone();
+
+        for (pos, len) in gaps {
+            spans[len].push(Reverse(pos));
+        }
+
+        for file in files.iter_mut().rev() {
+            let Some((pos, size)) = (file.len..spans.len())
+                .filter_map(|size| spans[size].peek().map(|&Reverse(pos)| (pos, size)))
+                .min()
+            else {
+                continue;
+            };
+
+            // files never move right, and space they vacate is past every lower id
+            if pos > file.pos {
+                continue;
+            }
+
+            spans[size].pop();
+            file.pos = pos;
+
+            if size > file.len {
+                spans[size - file.len].push(Reverse(pos + file.len));
+            }
+        }
+
+        files.sort_unstable_by_key(|f| f.pos);
+
+        Disk {
+            files,
+            size: self.size,
+        }
+    }
+
+    /// Free spans as `(pos, len)` in disk order, trailing space excluded.
+    fn gaps(&self) -> Vec<(usize, usize)> {
+        self.files
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| (a.pos + a.len, b.pos - a.pos - a.len))
+            .filter(|&(_, len)| len > 0)
+            .collect()
+    }
+}
@@ -1,14 +1,10 @@
 #![feature(test)]
 
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap, HashSet},
-    fs, iter,
-};
+mod disk;
 
-use auto_enums::auto_enum;
-use itertools::{Either, Itertools};
-use num::Integer;
+use std::fs;
+
+use disk::Disk;
 
 fn main() {
     let input = fs::read_to_string("./day09/_data/input.txt").expect("oh noes");
@@ -18,152 +14,21 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    let first_line = input.lines().find(|line| !line.is_empty()).unwrap();
-
-    let mut disk = Vec::with_capacity(first_line.len() * 8);
-    for (i, n) in first_line
-        .chars()
-        .filter_map(|ch| ch.to_digit(10))
-        .enumerate()
-        .map(|(i, n)| (if i.is_even() { Some(i / 2) } else { None }, n as usize))
-    {
-        // faster than flat_map
-        for _ in 0..n {
-            disk.push(i);
-        }
-    }
-
-    let mut defraged_disk = vec![];
-    let mut iter_disk = disk.into_iter();
-
-    while let Some(block) = iter_disk.next() {
-        // rfind continues from where it stopped last time
-        if let Some(x) = block.or_else(|| iter_disk.rfind(|y| y.is_some()).flatten()) {
-            defraged_disk.push(x);
-        } else {
-            break;
-        }
-    }
-
-    defraged_disk
-        .into_iter()
-        .enumerate()
-        .map(|(i, b)| b * i)
-        .sum::<usize>()
+    Disk::from(first_line(input))
+        .compact_blocks()
+        .checksum()
         .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let first_line = input.lines().find(|line| !line.is_empty()).unwrap();
-
-    let mut disk = first_line
-        .chars()
-        .filter_map(|ch| ch.to_digit(10).map(|n| n as usize))
-        .collect_vec();
-
-    let files = disk
-        .iter()
-        .enumerate()
-        .rev()
-        .filter_map(|(index, &size)| {
-            if index.is_even() {
-                Some((index, size))
-            } else {
-                None
-            }
-        })
-        .collect_vec();
-
-    let mut spaces: HashMap<_, BinaryHeap<_>> = HashMap::from_iter(
-        disk.iter()
-            .enumerate()
-            .filter_map(|(index, &size)| {
-                if index.is_odd() && size != 0 {
-                    Some((size, Reverse(index)))
-                } else {
-                    None
-                }
-            })
-            .into_group_map()
-            .into_iter()
-            .map(|(k, v)| (k, v.into())),
-    );
-
-    let mut moved: HashMap<usize, Vec<(_, _)>> = HashMap::new();
-    let mut removed = HashSet::new();
-
-    for (file_index, file_size) in files {
-        if let Some((space_size, space_index)) = (file_size..=9)
-            .filter_map(|size| {
-                spaces
-                    .get(&size)
-                    .and_then(|v| v.peek())
-                    .map(move |ix| (size, ix.0))
-            })
-            .k_smallest_by_key(1, |(_, ix)| *ix)
-            .next()
-        {
-            if space_index > file_index {
-                continue;
-            }
-
-            removed.insert(file_index);
-            moved
-                .entry(space_index)
-                .and_modify(|e| e.push((file_index, file_size)))
-                .or_insert_with(|| [(file_index, file_size)].into());
-
-            let new_space_size = space_size - file_size;
-            disk[space_index] = new_space_size;
-
-            spaces.get_mut(&space_size).map(|v| v.pop());
-
-            if new_space_size > 0 {
-                spaces
-                    .entry(new_space_size)
-                    .and_modify(|new| {
-                        new.push(Reverse(space_index));
-                    })
-                    .or_insert_with(|| [Reverse(space_index)].into());
-            }
-        }
-    }
-
-    disk.into_iter()
-        .enumerate()
-        .flat_map(|(index, size)| {
-            if index.is_even() {
-                if removed.contains(&index) {
-                    inflate(Either::Left(None), size)
-                } else {
-                    inflate(Either::Left(Some(index / 2)), size)
-                }
-            } else if let Some(m) = moved.get(&index) {
-                inflate(Either::Right(m), size)
-            } else {
-                inflate(Either::Left(None), size)
-            }
-        })
-        .enumerate()
-        .filter_map(|(i, b)| b.map(|b| b * i))
-        .sum::<usize>()
+    Disk::from(first_line(input))
+        .compact_files()
+        .checksum()
         .to_string()
 }
 
-#[auto_enum(Iterator)]
-fn inflate(
-    value: Either<Option<usize>, &[(usize, usize)]>,
-    count: usize,
-) -> impl IntoIterator<Item = Option<usize>> + use<'_> {
-    match value {
-        Either::Left(value) => iter::repeat_n(value, count),
-        Either::Right(moved) => iter::repeat((0usize, 0usize))
-            .zip(moved)
-            .flat_map(|((_, space_size), &(b_index, b_size))| {
-                iter::repeat_n(None, space_size).chain(iter::repeat_n(Some(b_index / 2), b_size))
-            })
-            .chain(iter::repeat_n(None, count)),
-    }
+fn first_line(input: &str) -> &str {
+    input.lines().find(|line| !line.is_empty()).unwrap()
 }
 
 #[cfg(test)]
@@ -171,7 +36,11 @@ mod tests {
     extern crate test;
 
     use crate::*;
+    use itertools::Itertools;
+    use num::Integer;
     use rstest::{fixture, rstest};
+    use std::iter;
+    use stress::{Rng, StdRng};
     use test::Bencher;
 
     const TEST_CASE: &str = "2333133121414131402";
@@ -193,6 +62,27 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case("12345", "0..111....22222")]
+    #[case(TEST_CASE, "00...111...2...333.44.5555.6666.777.888899")]
+    fn layout_check(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, Disk::from(input).to_string());
+    }
+
+    #[rstest]
+    #[case("12345", "022111222......")]
+    #[case(TEST_CASE, "0099811188827773336446555566..............")]
+    fn compact_blocks_check(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, Disk::from(input).compact_blocks().to_string());
+    }
+
+    #[rstest]
+    #[case(TEST_CASE, "00992111777.44.333....5555.6666.....8888..")]
+    #[case("1010101010101010101010", "0123456789[10]")]
+    fn compact_files_check(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(expected, Disk::from(input).compact_files().to_string());
+    }
+
     #[rstest]
     #[case("6201130364722")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -219,6 +109,80 @@ mod tests {
         assert_eq!(expected, process_part_2(&input));
     }
 
+    stress::register! {
+        generator: generate_disk_map,
+        sizes: [1, 3, 10, 30, 100],
+        part_1_stress: reference_part_1 => process_part_1,
+        part_2_stress: reference_part_2 => process_part_2,
+    }
+
+    fn generate_disk_map(rng: &mut StdRng, files: usize) -> String {
+        (0..files * 2 - 1)
+            .map(|i| {
+                if i.is_even() {
+                    rng.gen_range(1..=9)
+                } else {
+                    rng.gen_range(0..=9)
+                }
+            })
+            .map(|n| char::from_digit(n, 10).unwrap())
+            .collect()
+    }
+
+    fn expand(input: &str) -> Vec<Option<usize>> {
+        input
+            .trim()
+            .chars()
+            .filter_map(|ch| ch.to_digit(10))
+            .enumerate()
+            .flat_map(|(i, n)| iter::repeat_n(i.is_even().then_some(i / 2), n as usize))
+            .collect_vec()
+    }
+
+    fn checksum(disk: &[Option<usize>]) -> String {
+        disk.iter()
+            .enumerate()
+            .filter_map(|(i, b)| b.map(|b| b * i))
+            .sum::<usize>()
+            .to_string()
+    }
+
+    fn reference_part_1(input: &str) -> String {
+        let mut disk = expand(input);
+
+        loop {
+            let free = disk.iter().position(|b| b.is_none());
+            let last = disk.iter().rposition(|b| b.is_some());
+
+            match (free, last) {
+                (Some(free), Some(last)) if free < last => disk.swap(free, last),
+                _ => break,
+            }
+        }
+
+        checksum(&disk)
+    }
+
+    fn reference_part_2(input: &str) -> String {
+        let mut disk = expand(input);
+        let max_id = disk.iter().flatten().max().copied().unwrap_or(0);
+
+        for id in (0..=max_id).rev() {
+            let start = disk.iter().position(|b| *b == Some(id)).unwrap();
+            let len = disk[start..].iter().take_while(|b| **b == Some(id)).count();
+
+            let target = (0..start).find(|&i| disk[i..i + len].iter().all(|b| b.is_none()));
+
+            if let Some(target) = target {
+                for i in 0..len {
+                    disk.swap(target + i, start + i);
+                }
+            }
+        }
+
+        checksum(&disk)
+    }
+
     #[bench]
     fn part_1_check_bench(b: &mut Bencher) {
         b.iter(|| process_part_1(TEST_CASE));
@@ -1,27 +1,120 @@
 use fxhash::FxHashMap;
+use itertools::Itertools;
 
-use super::tile::Tile;
+use super::{r#move::Move, tile::Tile};
 
-#[derive(Debug)]
-pub enum Field {
-    Normal(FxHashMap<(isize, isize), Tile>),
-    Wide(FxHashMap<(isize, isize), Tile>),
+/// Cell whose tile differs between two fields, as `(pos, before, after)`.
+#[cfg(test)]
+pub type TileChange = ((isize, isize), Option<Tile>, Option<Tile>);
+
+/// Tiles keyed by their leftmost cell, every tile spans `width` columns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    tiles: FxHashMap<(isize, isize), Tile>,
+    width: isize,
+    rows: isize,
+    cols: isize,
 }
 
 impl Field {
-    pub fn sum_boxes(self) -> isize {
-        match self {
-            Field::Normal(field) => field,
-            Field::Wide(field) => field,
+    pub fn new(
+        tiles: FxHashMap<(isize, isize), Tile>,
+        width: isize,
+        rows: isize,
+        cols: isize,
+    ) -> Self {
+        Field {
+            tiles,
+            width,
+            rows,
+            cols,
         }
-        .into_iter()
-        .filter_map(|((row, col), tile)| {
-            if tile == Tile::Box {
-                Some(row * 100 + col)
-            } else {
-                None
-            }
+    }
+
+    pub fn sum_boxes(&self) -> isize {
+        self.tiles
+            .iter()
+            .filter_map(|(&(row, col), &tile)| {
+                if tile == Tile::Box {
+                    Some(row * 100 + col)
+                } else {
+                    None
+                }
+            })
+            .sum()
+    }
+
+    /// Tile covering the given cell along with its leftmost cell.
+    pub fn tile_at(&self, (row, col): (isize, isize)) -> Option<((isize, isize), Tile)> {
+        (0..self.width).find_map(|offset| {
+            self.tiles
+                .get_key_value(&(row, col - offset))
+                .map(|(&pos, &tile)| (pos, tile))
         })
-        .sum()
+    }
+
+    /// Cells a tile would newly cover after being pushed.
+    pub fn cells_ahead(&self, (row, col): (isize, isize), mv: Move) -> Vec<(isize, isize)> {
+        match mv {
+            Move::Left => vec![(row, col - 1)],
+            Move::Right => vec![(row, col + self.width)],
+            Move::Up | Move::Down => (col..col + self.width)
+                .map(|c| mv.apply_to((row, c)))
+                .collect_vec(),
+        }
+    }
+
+    pub fn shift_boxes(&mut self, boxes: &[(isize, isize)], mv: Move) {
+        for pos in boxes {
+            self.tiles.remove(pos);
+        }
+
+        for &pos in boxes {
+            self.tiles.insert(mv.apply_to(pos), Tile::Box);
+        }
+    }
+
+    #[cfg(test)]
+    pub fn render(&self, robot: (isize, isize)) -> String {
+        let mut grid = vec![vec!['.'; self.cols as usize]; self.rows as usize];
+
+        for (&(row, col), &tile) in self.tiles.iter() {
+            let symbols = match (tile, self.width) {
+                (Tile::Wall, w) => vec!['#'; w as usize],
+                (Tile::Box, 1) => vec!['O'],
+                (Tile::Box, w) => std::iter::once('[')
+                    .chain(std::iter::repeat_n('=', w as usize - 2))
+                    .chain(std::iter::once(']'))
+                    .collect_vec(),
+            };
+
+            for (offset, symbol) in symbols.into_iter().enumerate() {
+                grid[row as usize][col as usize + offset] = symbol;
+            }
+        }
+
+        grid[robot.0 as usize][robot.1 as usize] = '@';
+
+        grid.into_iter()
+            .map(|line| line.into_iter().collect::<String>())
+            .join("\n")
+    }
+
+    #[cfg(test)]
+    pub fn diff(&self, other: &Field) -> Vec<TileChange> {
+        self.tiles
+            .keys()
+            .chain(other.tiles.keys())
+            .unique()
+            .map(|pos| {
+                (
+                    *pos,
+                    self.tiles.get(pos).copied(),
+                    other.tiles.get(pos).copied(),
+                )
+            })
+            .filter(|(_, a, b)| a != b)
+            .sorted_by_key(|(pos, _, _)| *pos)
+            .collect_vec()
     }
 }
@@ -1,5 +1,7 @@
 mod field;
 mod r#move;
+#[cfg(test)]
+mod replay;
 mod robot;
 mod tile;
 mod warehouse;
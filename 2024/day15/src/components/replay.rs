@@ -0,0 +1,37 @@
+use super::{
+    field::{Field, TileChange},
+    r#move::Move,
+};
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub mv: Option<Move>,
+    pub robot: (isize, isize),
+    pub field: Field,
+}
+
+/// Every intermediate state of a simulation,
+/// frame `0` is the initial state and frame `i` follows move `i - 1`.
+#[derive(Debug, Clone, Default)]
+pub struct Replay {
+    pub frames: Vec<Frame>,
+}
+
+impl Replay {
+    pub fn render(&self, index: usize) -> Option<String> {
+        self.frames
+            .get(index)
+            .map(|frame| frame.field.render(frame.robot))
+    }
+
+    /// Tiles changed by the move leading to the given frame.
+    pub fn diff(&self, index: usize) -> Vec<TileChange> {
+        match (
+            index.checked_sub(1).and_then(|i| self.frames.get(i)),
+            self.frames.get(index),
+        ) {
+            (Some(prev), Some(cur)) => prev.field.diff(&cur.field),
+            _ => vec![],
+        }
+    }
+}
@@ -1,6 +1,5 @@
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Tile {
-    None,
     Box,
     Wall,
 }
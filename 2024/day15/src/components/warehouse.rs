@@ -1,6 +1,8 @@
 use fxhash::FxHashMap;
 use itertools::Itertools;
 
+#[cfg(test)]
+use super::replay::{Frame, Replay};
 use super::{field::Field, r#move::Move, robot::Robot, tile::Tile};
 
 #[derive(Debug)]
@@ -11,14 +13,17 @@ pub struct Warehouse {
 }
 
 impl Warehouse {
-    pub fn new(input: &str, is_wide: bool) -> Self {
-        let col_mult = if is_wide { 2 } else { 1 };
+    /// Parses the map with every wall and box stretched to `width` columns.
+    pub fn new(input: &str, width: usize) -> Self {
+        let col_mult = width as isize;
 
         let mut lines_iter = input.lines().skip_while(|line| line.is_empty());
         let lines_iter = lines_iter.by_ref();
 
         let mut robot = None;
         let mut field = FxHashMap::default();
+        let mut rows = 0;
+        let mut cols = 0;
 
         for ((row, col), ch) in lines_iter
             .take_while(|line| !line.is_empty())
@@ -28,6 +33,9 @@ impl Warehouse {
                     .map(move |(col, ch)| ((row as isize, col as isize), ch))
             })
         {
+            rows = rows.max(row + 1);
+            cols = cols.max((col + 1) * col_mult);
+
             match ch {
                 '#' => {
                     field.insert((row, col * col_mult), Tile::Wall);
@@ -51,11 +59,7 @@ impl Warehouse {
         Warehouse {
             robot: Robot::new(robot.unwrap()),
             moves,
-            field: if is_wide {
-                Field::Wide(field)
-            } else {
-                Field::Normal(field)
-            },
+            field: Field::new(field, col_mult, rows, cols),
         }
     }
 
@@ -72,4 +76,32 @@ impl Warehouse {
 
         field.sum_boxes()
     }
+
+    /// Simulates while recording the field after every move.
+    #[cfg(test)]
+    pub fn replay(self) -> Replay {
+        let Warehouse {
+            mut robot,
+            moves,
+            mut field,
+        } = self;
+
+        let mut frames = vec![Frame {
+            mv: None,
+            robot: robot.position(),
+            field: field.clone(),
+        }];
+
+        for mv in moves {
+            robot.apply_move(mv, &mut field);
+
+            frames.push(Frame {
+                mv: Some(mv),
+                robot: robot.position(),
+                field: field.clone(),
+            });
+        }
+
+        Replay { frames }
+    }
 }
@@ -14,11 +14,11 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    Warehouse::new(input, false).simulate().to_string()
+    Warehouse::new(input, 1).simulate().to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    Warehouse::new(input, true).simulate().to_string()
+    Warehouse::new(input, 2).simulate().to_string()
 }
 
 #[cfg(test)]
@@ -94,6 +94,74 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case(TEST_CASE_2, 1, 10092)]
+    #[case(TEST_CASE_2, 2, 9021)]
+    #[case(TEST_CASE_3, 3, 927)]
+    fn width_check(#[case] input: &str, #[case] width: usize, #[case] expected: isize) {
+        assert_eq!(expected, Warehouse::new(input, width).simulate());
+    }
+
+    #[rstest]
+    #[case(TEST_CASE_2, 1)]
+    #[case(TEST_CASE_2, 2)]
+    #[case(TEST_CASE_3, 3)]
+    fn replay_check(#[case] input: &str, #[case] width: usize) {
+        let replay = Warehouse::new(input, width).replay();
+        let last = replay.frames.last().unwrap();
+
+        assert_eq!(None, replay.frames[0].mv);
+        assert!(replay.frames[1..].iter().all(|f| f.mv.is_some()));
+        assert_eq!(
+            Warehouse::new(input, width).simulate(),
+            last.field.sum_boxes()
+        );
+    }
+
+    #[test]
+    fn replay_render_check() {
+        let replay = Warehouse::new(TEST_CASE_3, 2).replay();
+
+        assert_eq!(
+            "\
+##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############",
+            replay.render(0).unwrap()
+        );
+
+        assert_eq!(
+            "\
+##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############",
+            replay.render(replay.frames.len() - 1).unwrap()
+        );
+
+        let changed = replay
+            .diff(1)
+            .into_iter()
+            .map(|(pos, before, after)| (pos, before.is_some(), after.is_some()));
+        assert_eq!(
+            vec![
+                ((3, 5), false, true),
+                ((3, 6), true, false),
+                ((3, 7), false, true),
+                ((3, 8), true, false),
+            ],
+            changed.collect::<Vec<_>>()
+        );
+        assert!(replay.diff(2).is_empty());
+    }
+
     #[rstest]
     #[case("1499739")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -0,0 +1,204 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use fxhash::{FxHashMap, FxHashSet};
+use itertools::Itertools;
+
+pub const NUMERIC: &str = "789\n456\n123\n 0A";
+pub const DIRECTIONAL: &str = " ^A\n<v>";
+
+/// Keys laid out on a grid, spaces are gaps the arm must never pass over.
+/// Every arm starts on `A`.
+#[derive(Debug, Clone)]
+pub struct Keypad {
+    positions: FxHashMap<char, (isize, isize)>,
+    cells: FxHashSet<(isize, isize)>,
+}
+
+impl TryFrom<&str> for Keypad {
+    type Error = String;
+
+    fn try_from(layout: &str) -> Result<Self, Self::Error> {
+        let mut positions = FxHashMap::default();
+
+        for (row, line) in layout.lines().enumerate() {
+            for (col, key) in line.chars().enumerate().filter(|(_, key)| *key != ' ') {
+                if positions
+                    .insert(key, (row as isize, col as isize))
+                    .is_some()
+                {
+                    return Err(format!("Duplicate key: {}", key));
+                }
+            }
+        }
+
+        if !positions.contains_key(&'A') {
+            return Err(format!("No A key in layout: {:?}", layout));
+        }
+
+        let cells = positions.values().copied().collect::<FxHashSet<_>>();
+        let mut reached = FxHashSet::from_iter([positions[&'A']]);
+        let mut stack = vec![positions[&'A']];
+
+        while let Some((row, col)) = stack.pop() {
+            for next in [
+                (row - 1, col),
+                (row + 1, col),
+                (row, col - 1),
+                (row, col + 1),
+            ] {
+                if cells.contains(&next) && reached.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+
+        if reached.len() != cells.len() {
+            return Err(format!("Not every key is reachable: {:?}", layout));
+        }
+
+        Ok(Keypad { positions, cells })
+    }
+}
+
+impl Keypad {
+    pub fn keys(&self) -> impl Iterator<Item = char> + '_ {
+        self.positions.keys().copied()
+    }
+
+    /// Cheapest way for the arm to go from `from` to `to` and press it, when
+    /// `below[(a, b)]` is the price of the controlling arm going from `a` to
+    /// `b` and pressing. Dijkstra over the arm cell and the last controller
+    /// key, so detours around any shape of gaps are found.
+    fn route(&self, from: char, to: char, below: &FxHashMap<(char, char), Route>) -> Route {
+        const MOVES: [(char, (isize, isize)); 4] =
+            [('^', (-1, 0)), ('v', (1, 0)), ('<', (0, -1)), ('>', (0, 1))];
+
+        let start = (self.positions[&from], 'A');
+        let goal = self.positions[&to];
+        let mut best = FxHashMap::from_iter([(start, 0)]);
+        let mut prev = FxHashMap::default();
+        let mut queue = BinaryHeap::from([Reverse((0, start))]);
+        let mut finish = None;
+
+        while let Some(Reverse((cost, (pos, key)))) = queue.pop() {
+            if best.get(&(pos, key)).is_some_and(|&b| b < cost) {
+                continue;
+            }
+
+            if pos == goal {
+                let total = cost + below[&(key, 'A')].cost;
+
+                if finish.is_none_or(|(best, _)| total < best) {
+                    finish = Some((total, (pos, key)));
+                }
+            }
+
+            for (mv, (dr, dc)) in MOVES {
+                let next = ((pos.0 + dr, pos.1 + dc), mv);
+                let next_cost = cost + below[&(key, mv)].cost;
+
+                if self.cells.contains(&next.0) && best.get(&next).is_none_or(|&b| next_cost < b) {
+                    best.insert(next, next_cost);
+                    prev.insert(next, (pos, key));
+                    queue.push(Reverse((next_cost, next)));
+                }
+            }
+        }
+
+        let (cost, mut state) = finish.unwrap();
+        let mut keys = vec!['A'];
+
+        while state != start {
+            keys.push(state.1);
+            state = prev[&state];
+        }
+
+        Route {
+            cost,
+            keys: keys.into_iter().rev().collect(),
+        }
+    }
+
+    fn routes(&self, below: &FxHashMap<(char, char), Route>) -> FxHashMap<(char, char), Route> {
+        self.keys()
+            .cartesian_product(self.keys().collect_vec())
+            .map(|(from, to)| ((from, to), self.route(from, to, below)))
+            .collect()
+    }
+}
+
+/// Keys one arm above has to type to move this arm and press, with the hand presses that costs.
+#[derive(Debug, Clone)]
+struct Route {
+    cost: usize,
+    keys: String,
+}
+
+/// A target keypad operated through `indirections` robots on controller
+/// keypads, the outermost controller pressed by hand.
+pub struct KeypadChain {
+    /// `levels[depth][(from, to)]` is the route for the arm at controller
+    /// `depth`, depth 0 is the hand and the last level is the target arm.
+    levels: Vec<FxHashMap<(char, char), Route>>,
+}
+
+impl KeypadChain {
+    pub fn new(target: Keypad, controller: Keypad, indirections: usize) -> Result<Self, String> {
+        if let Some(missing) = "^v<>"
+            .chars()
+            .find(|key| !controller.positions.contains_key(key))
+        {
+            return Err(format!("Controller has no {} key", missing));
+        }
+
+        let hand = controller
+            .keys()
+            .cartesian_product(controller.keys().collect_vec())
+            .map(|(from, to)| {
+                let route = Route {
+                    cost: 1,
+                    keys: to.to_string(),
+                };
+                ((from, to), route)
+            })
+            .collect();
+        let mut levels = vec![hand];
+
+        for _ in 0..indirections {
+            levels.push(controller.routes(levels.last().unwrap()));
+        }
+
+        levels.push(target.routes(levels.last().unwrap()));
+
+        Ok(KeypadChain { levels })
+    }
+
+    /// Fewest hand presses that make the target arm type `code`.
+    pub fn cost(&self, code: &str) -> usize {
+        let top = self.levels.last().unwrap();
+
+        pairs(code).map(|pair| top[&pair].cost).sum()
+    }
+
+    /// One optimal hand sequence for `code`, its length grows exponentially
+    /// with the indirections so this is meant for small chains.
+    pub fn sequence(&self, code: &str) -> String {
+        self.expand(self.levels.len() - 1, code)
+    }
+
+    /// Hand presses making the arm at `depth` type `keys`.
+    fn expand(&self, depth: usize, keys: &str) -> String {
+        if depth == 0 {
+            return keys.to_owned();
+        }
+
+        pairs(keys)
+            .map(|pair| self.expand(depth - 1, &self.levels[depth][&pair].keys))
+            .collect()
+    }
+}
+
+/// Consecutive key pairs of a sequence typed by an arm resting on `A`.
+fn pairs(keys: &str) -> impl Iterator<Item = (char, char)> + '_ {
+    "A".chars().chain(keys.chars()).tuple_windows()
+}
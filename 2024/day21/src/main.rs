@@ -1,16 +1,25 @@
 #![feature(test)]
 
-use std::{fs, hash::Hash, sync::LazyLock};
+mod keypad;
+
+use std::fs;
 
-use cached::proc_macro::cached;
-use fxhash::FxHashMap;
 use itertools::Itertools;
+use keypad::{Keypad, KeypadChain, DIRECTIONAL, NUMERIC};
 
 fn main() {
     let input = fs::read_to_string("./day21/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
+
+    if cfg!(feature = "report") {
+        let chain = door_chain(2);
+
+        for code in parse(&input) {
+            println!("{}: {}", code, chain.sequence(code));
+        }
+    }
 }
 
 fn process_part_1(input: &str) -> String {
@@ -21,185 +30,43 @@ fn process_part_2(input: &str) -> String {
     type_passwords(input, 25).to_string()
 }
 
-static NUM_POSITIONS: LazyLock<FxHashMap<isize, (isize, isize)>> = LazyLock::new(|| {
-    FxHashMap::from_iter([
-        (0, (3, 1)),
-        (1, (2, 0)),
-        (2, (2, 1)),
-        (3, (2, 2)),
-        (4, (1, 0)),
-        (5, (1, 1)),
-        (6, (1, 2)),
-        (7, (0, 0)),
-        (8, (0, 1)),
-        (9, (0, 2)),
-        (10, (3, 2)),
-    ])
-});
-
-static DIR_POSITIONS: LazyLock<FxHashMap<DirectionalSymbol, (isize, isize)>> =
-    LazyLock::new(|| {
-        FxHashMap::from_iter([
-            (DirectionalSymbol::Up, (0, 1)),
-            (DirectionalSymbol::Down, (1, 1)),
-            (DirectionalSymbol::Left, (1, 0)),
-            (DirectionalSymbol::Right, (1, 2)),
-            (DirectionalSymbol::A, (0, 2)),
-        ])
-    });
-
-#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
-enum DirectionalSymbol {
-    Up,
-    Down,
-    Left,
-    Right,
-    A,
+fn door_chain(indirections: usize) -> KeypadChain {
+    let numeric = Keypad::try_from(NUMERIC).unwrap();
+    let directional = Keypad::try_from(DIRECTIONAL).unwrap();
+
+    KeypadChain::new(numeric, directional, indirections).unwrap()
 }
 
-fn parse(input: &str) -> Vec<(Vec<isize>, usize)> {
-    input
-        .lines()
-        .filter(|line| !line.is_empty())
-        .map(|line| {
-            (
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap_or(10) as isize)
-                    .collect_vec(),
-                line[..3].parse().unwrap(),
-            )
-        })
-        .collect_vec()
+fn parse(input: &str) -> Vec<&str> {
+    input.lines().filter(|line| !line.is_empty()).collect_vec()
 }
 
 fn type_passwords(input: &str, indirections: usize) -> usize {
+    let chain = door_chain(indirections);
+
     parse(input)
         .into_iter()
-        .map(|(v, n)| {
-            n * numbers_into_directions(v)
-                .into_iter()
-                .map(|d| reduce_directions(d, indirections))
-                .sum::<usize>()
+        .map(|code| {
+            let value = code
+                .chars()
+                .filter(|ch| ch.is_ascii_digit())
+                .collect::<String>()
+                .parse::<usize>()
+                .unwrap_or(0);
+
+            value * chain.cost(code)
         })
         .sum()
 }
 
-#[cached(
-    key = "(String, usize)",
-    convert = r#"{(format!("{directions:?}"), nest)}"#
-)]
-fn reduce_directions(directions: Vec<Vec<DirectionalSymbol>>, nest: usize) -> usize {
-    if nest == 0 {
-        directions.into_iter().map(|d| d.len()).min().unwrap()
-    } else {
-        directions
-            .into_iter()
-            .map(|d| {
-                directions_into_directions(d)
-                    .into_iter()
-                    .map(|nd| reduce_directions(nd, nest - 1))
-                    .sum()
-            })
-            .min()
-            .unwrap()
-    }
-}
-
-fn next_numeric(current: isize, target: isize) -> Vec<Vec<DirectionalSymbol>> {
-    next(&NUM_POSITIONS, current, target, (3, 0))
-}
-
-fn next_direction(
-    current: DirectionalSymbol,
-    target: DirectionalSymbol,
-) -> Vec<Vec<DirectionalSymbol>> {
-    next(&DIR_POSITIONS, current, target, (0, 0))
-}
-
-fn next<K: Hash + Eq>(
-    map: &FxHashMap<K, (isize, isize)>,
-    current: K,
-    target: K,
-    missing: (isize, isize),
-) -> Vec<Vec<DirectionalSymbol>> {
-    let curr_pos = map.get(&current).unwrap();
-    let tar_pos = map.get(&target).unwrap();
-
-    let horz_count = tar_pos.1.abs_diff(curr_pos.1);
-    let horz_dir = (tar_pos.1 - curr_pos.1).signum();
-    let horz = vec![
-        if horz_dir > 0 {
-            DirectionalSymbol::Right
-        } else {
-            DirectionalSymbol::Left
-        };
-        horz_count
-    ];
-
-    let vert_count = tar_pos.0.abs_diff(curr_pos.0);
-    let vert_dir = (tar_pos.0 - curr_pos.0).signum();
-    let vert = vec![
-        if vert_dir > 0 {
-            DirectionalSymbol::Down
-        } else {
-            DirectionalSymbol::Up
-        };
-        vert_count
-    ];
-
-    if horz.is_empty() {
-        let mut vert = vert;
-        vert.push(DirectionalSymbol::A);
-        return vec![vert];
-    }
-
-    if vert.is_empty() {
-        let mut horz = horz;
-        horz.push(DirectionalSymbol::A);
-        return vec![horz];
-    }
-
-    let mut res = vec![];
-    if curr_pos.1 != missing.1 || tar_pos.0 != missing.0 {
-        let mut path = [vert.clone(), horz.clone()].concat();
-        path.push(DirectionalSymbol::A);
-        res.push(path);
-    }
-    if curr_pos.0 != missing.0 || tar_pos.1 != missing.1 {
-        let mut path = [horz.clone(), vert.clone()].concat();
-        path.push(DirectionalSymbol::A);
-        res.push(path);
-    }
-
-    res
-}
-
-fn numbers_into_directions(values: Vec<isize>) -> Vec<Vec<Vec<DirectionalSymbol>>> {
-    let mut current = 10;
-    let mut res = vec![];
-    for value in values {
-        res.push(next_numeric(current, value));
-        current = value;
-    }
-    res
-}
-
-fn directions_into_directions(values: Vec<DirectionalSymbol>) -> Vec<Vec<Vec<DirectionalSymbol>>> {
-    let mut current = DirectionalSymbol::A;
-    let mut res = vec![];
-    for value in values {
-        res.push(next_direction(current, value));
-        current = value;
-    }
-    res
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
 
     use crate::*;
+    use fxhash::FxHashSet;
     use rstest::{fixture, rstest};
+    use std::collections::VecDeque;
     use test::Bencher;
 
     const TEST_CASE: &str = "
@@ -226,6 +93,147 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case("029A", 0, 12)]
+    #[case("029A", 1, 28)]
+    #[case("029A", 2, 68)]
+    #[case("980A", 2, 60)]
+    #[case("179A", 2, 68)]
+    #[case("456A", 2, 64)]
+    #[case("379A", 2, 64)]
+    fn sequence_check(#[case] code: &str, #[case] indirections: usize, #[case] expected: usize) {
+        let chain = door_chain(indirections);
+        let sequence = chain.sequence(code);
+        let layouts = [vec![DIRECTIONAL; indirections], vec![NUMERIC]].concat();
+
+        assert_eq!(expected, chain.cost(code));
+        assert_eq!(expected, sequence.len());
+        assert_eq!(Some(code.to_owned()), simulate(&layouts, &sequence));
+    }
+
+    #[rstest]
+    #[case("1A", 0)]
+    #[case("21A", 1)]
+    #[case("A12", 1)]
+    #[case("5A2", 2)]
+    fn custom_layout_check(#[case] code: &str, #[case] indirections: usize) {
+        const PAD: &str = "1 2\n3 4\n56A";
+
+        let target = Keypad::try_from(PAD).unwrap();
+        let controller = Keypad::try_from(DIRECTIONAL).unwrap();
+        let chain = KeypadChain::new(target, controller, indirections).unwrap();
+        let layouts = [vec![DIRECTIONAL; indirections], vec![PAD]].concat();
+
+        assert_eq!(shortest(&layouts, code), chain.cost(code));
+        assert_eq!(
+            Some(code.to_owned()),
+            simulate(&layouts, &chain.sequence(code))
+        );
+    }
+
+    #[rstest]
+    #[case("12\n3")]
+    #[case("AA")]
+    #[case("1 A")]
+    fn invalid_layout_check(#[case] layout: &str) {
+        assert!(Keypad::try_from(layout).is_err());
+    }
+
+    #[test]
+    fn invalid_controller_check() {
+        let numeric = Keypad::try_from(NUMERIC).unwrap();
+
+        assert!(KeypadChain::new(numeric.clone(), numeric, 1).is_err());
+    }
+
+    fn grid(layout: &str) -> Vec<Vec<char>> {
+        layout
+            .lines()
+            .map(|l| l.chars().collect_vec())
+            .collect_vec()
+    }
+
+    fn find(layout: &[Vec<char>], key: char) -> (usize, usize) {
+        (0..layout.len())
+            .flat_map(|r| (0..layout[r].len()).map(move |c| (r, c)))
+            .find(|&(r, c)| layout[r][c] == key)
+            .unwrap()
+    }
+
+    /// Applies one hand press, returns the key the last arm pressed if any,
+    /// `Err` if an arm panics over a gap or off the pad.
+    fn press(
+        layouts: &[Vec<Vec<char>>],
+        arms: &mut [(usize, usize)],
+        key: char,
+    ) -> Result<Option<char>, ()> {
+        let mut key = key;
+
+        for (layout, arm) in layouts.iter().zip(arms.iter_mut()) {
+            if key != 'A' {
+                let (r, c) = *arm;
+                *arm = match key {
+                    '^' => (r.checked_sub(1).ok_or(())?, c),
+                    'v' => (r + 1, c),
+                    '<' => (r, c.checked_sub(1).ok_or(())?),
+                    _ => (r, c + 1),
+                };
+
+                let cell = layout.get(arm.0).and_then(|row| row.get(arm.1));
+                return match cell {
+                    Some(' ') | None => Err(()),
+                    Some(_) => Ok(None),
+                };
+            }
+
+            key = layout[arm.0][arm.1];
+        }
+
+        Ok(Some(key))
+    }
+
+    fn simulate(layouts: &[&str], sequence: &str) -> Option<String> {
+        let layouts = layouts.iter().map(|l| grid(l)).collect_vec();
+        let mut arms = layouts.iter().map(|l| find(l, 'A')).collect_vec();
+        let mut typed = String::new();
+
+        for key in sequence.chars() {
+            typed.extend(press(&layouts, &mut arms, key).ok()?);
+        }
+
+        Some(typed)
+    }
+
+    /// Breadth-first search over every arm position and typed prefix.
+    fn shortest(layouts: &[&str], code: &str) -> usize {
+        let layouts = layouts.iter().map(|l| grid(l)).collect_vec();
+        let start = layouts.iter().map(|l| find(l, 'A')).collect_vec();
+        let mut seen = FxHashSet::from_iter([(start.clone(), 0)]);
+        let mut queue = VecDeque::from([(start, 0, 0)]);
+
+        while let Some((arms, typed, presses)) = queue.pop_front() {
+            if typed == code.len() {
+                return presses;
+            }
+
+            for key in "^v<>A".chars() {
+                let mut next = arms.clone();
+                let next_typed = match press(&layouts, &mut next, key) {
+                    Err(()) => continue,
+                    Ok(None) => typed,
+                    Ok(Some(ch)) if code[typed..].starts_with(ch) => typed + 1,
+                    Ok(Some(_)) => continue,
+                };
+
+                if seen.insert((next.clone(), next_typed)) {
+                    queue.push_back((next, next_typed, presses + 1));
+                }
+            }
+        }
+
+        unreachable!()
+    }
+
     #[rstest]
     #[case("155252")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -1,58 +1,55 @@
 #![feature(test)]
 
-use std::{fs, iter};
+mod market;
 
-use fxhash::{FxHashMap, FxHashSet};
-use itertools::Itertools;
+use std::fs;
+
+use market::{best_sale, next_secret, Sale};
 
 fn main() {
     let input = fs::read_to_string("./day22/_data/input.txt").expect("oh noes");
 
     println!("Part 1 result is: {}", process_part_1(&input));
     println!("Part 2 result is: {}", process_part_2(&input));
+
+    if cfg!(feature = "report") {
+        if let Some(sale) = best_sale(&parse(&input), 2000) {
+            report(&sale);
+        }
+    }
+}
+
+/// Winning sequence and what every buyer sells at, printed with the `report` feature.
+fn report(sale: &Sale) {
+    println!("Sequence {:?} earns {}", sale.sequence, sale.total);
+
+    for (buyer, price) in sale.prices.iter().enumerate() {
+        match price {
+            Some(price) => println!("Buyer {}: {}", buyer, price),
+            None => println!("Buyer {}: no sale", buyer),
+        }
+    }
 }
 
 fn process_part_1(input: &str) -> String {
-    input
-        .lines()
-        .filter_map(|line| line.parse::<isize>().ok())
-        .map(|n| (0..2000).fold(n, |prev, _| get_next_secret(prev)))
+    parse(input)
+        .into_iter()
+        .map(|n| (0..2000).fold(n, |prev, _| next_secret(prev)))
         .sum::<isize>()
         .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let mut map = FxHashMap::default();
-    let mut found = FxHashSet::default();
-
-    for mut n in input.lines().filter_map(|line| line.parse::<isize>().ok()) {
-        found.clear();
-
-        for (a, b, c, d, e) in iter::repeat_with(|| {
-            n = get_next_secret(n);
-            n % 10
-        })
-        .take(2000)
-        .tuple_windows()
-        {
-            let key = (b - a, c - b, d - c, e - d);
-            if !found.contains(&key) {
-                found.insert(key);
-                map.entry(key).and_modify(|v| *v += e).or_insert(e);
-            }
-        }
-    }
-
-    map.into_values().max().unwrap().to_string()
+    best_sale(&parse(input), 2000)
+        .map_or(0, |sale| sale.total)
+        .to_string()
 }
 
-fn get_next_secret(mut n: isize) -> isize {
-    n ^= n << 6;
-    n &= 16777215;
-    n ^= n >> 5;
-    n ^= n << 11;
-    n &= 16777215;
-    n
+fn parse(input: &str) -> Vec<isize> {
+    input
+        .lines()
+        .filter_map(|line| line.parse::<isize>().ok())
+        .collect()
 }
 
 #[cfg(test)]
@@ -60,7 +57,10 @@ mod tests {
     extern crate test;
 
     use crate::*;
+    use fxhash::{FxHashMap, FxHashSet};
+    use itertools::Itertools;
     use rstest::{fixture, rstest};
+    use stress::{Rng, StdRng};
     use test::Bencher;
 
     const TEST_CASE_1: &str = "
@@ -92,6 +92,51 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[test]
+    fn sale_check() {
+        let expected = Sale {
+            sequence: [-2, 1, -1, 3],
+            total: 23,
+            prices: vec![Some(7), Some(7), None, Some(9)],
+        };
+
+        assert_eq!(Some(expected), best_sale(&parse(TEST_CASE_2), 2000));
+    }
+
+    stress::register! {
+        generator: generate_seeds,
+        sizes: [1, 5, 20],
+        part_2_stress: reference_part_2 => process_part_2,
+    }
+
+    fn generate_seeds(rng: &mut StdRng, size: usize) -> String {
+        (0..size).map(|_| rng.gen_range(1..16777216)).join("\n")
+    }
+
+    fn reference_part_2(input: &str) -> String {
+        let mut totals = FxHashMap::default();
+
+        for seed in parse(input) {
+            let mut found = FxHashSet::default();
+            let prices = (0..2000)
+                .scan(seed, |n, _| {
+                    *n = next_secret(*n);
+                    Some(*n % 10)
+                })
+                .collect_vec();
+
+            for (a, b, c, d, e) in std::iter::once(seed % 10).chain(prices).tuple_windows() {
+                let key = (b - a, c - b, d - c, e - d);
+
+                if found.insert(key) {
+                    *totals.entry(key).or_insert(0) += e;
+                }
+            }
+        }
+
+        totals.into_values().max().unwrap_or(0).to_string()
+    }
+
     #[rstest]
     #[case("18941802053")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -0,0 +1,123 @@
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+/// Price changes range over -9..=9, so four of them index a dense table.
+const CHANGES: usize = 19;
+const SEQUENCES: usize = CHANGES.pow(4);
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Sale {
+    /// Four price changes the monkey waits for, oldest first.
+    pub sequence: [isize; 4],
+    pub total: usize,
+    /// Price each buyer sells at, `None` if the sequence never shows up.
+    pub prices: Vec<Option<usize>>,
+}
+
+/// Buyers handled by one rayon worker, `seen[seq]` holds the stamp
+/// of the last buyer who already sold on that sequence.
+struct Accumulator {
+    totals: Vec<usize>,
+    seen: Vec<usize>,
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        Accumulator {
+            totals: vec![0; SEQUENCES],
+            seen: vec![usize::MAX; SEQUENCES],
+        }
+    }
+
+    fn add_buyer(mut self, stamp: usize, seed: isize, rounds: usize) -> Self {
+        for (seq, price) in first_sales(seed, rounds) {
+            if self.seen[seq] != stamp {
+                self.seen[seq] = stamp;
+                self.totals[seq] += price;
+            }
+        }
+
+        self
+    }
+}
+
+pub fn next_secret(mut n: isize) -> isize {
+    n ^= n << 6;
+    n &= 16777215;
+    n ^= n >> 5;
+    n ^= n << 11;
+    n &= 16777215;
+    n
+}
+
+/// Change sequence that earns the most bananas over all buyers,
+/// ties go to the lowest sequence in -9..=9 lexicographic order.
+pub fn best_sale(seeds: &[isize], rounds: usize) -> Option<Sale> {
+    let totals = seeds
+        .par_iter()
+        .enumerate()
+        .fold(Accumulator::new, |acc, (stamp, &seed)| {
+            acc.add_buyer(stamp, seed, rounds)
+        })
+        .map(|acc| acc.totals)
+        .reduce(
+            || vec![0; SEQUENCES],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                a
+            },
+        );
+
+    let (index, &total) = totals
+        .iter()
+        .enumerate()
+        .rev()
+        .max_by_key(|(_, &total)| total)?;
+
+    if total == 0 {
+        return None;
+    }
+
+    let prices = seeds
+        .par_iter()
+        .map(|&seed| {
+            first_sales(seed, rounds)
+                .find(|&(seq, _)| seq == index)
+                .map(|(_, price)| price)
+        })
+        .collect();
+
+    Some(Sale {
+        sequence: decode(index),
+        total,
+        prices,
+    })
+}
+
+/// Every `(sequence, price)` along a buyer's prices once four changes are known,
+/// a sequence may repeat and only its first occurrence is a sale.
+fn first_sales(seed: isize, rounds: usize) -> impl Iterator<Item = (usize, usize)> {
+    let mut secret = seed;
+    let mut prev = seed % 10;
+    let mut index = 0;
+
+    (1..=rounds).filter_map(move |round| {
+        secret = next_secret(secret);
+        let price = secret % 10;
+
+        index = (index * CHANGES + (price - prev + 9) as usize) % SEQUENCES;
+        prev = price;
+
+        (round >= 4).then_some((index, price as usize))
+    })
+}
+
+fn decode(mut index: usize) -> [isize; 4] {
+    let mut sequence = [0; 4];
+
+    for change in sequence.iter_mut().rev() {
+        *change = (index % CHANGES) as isize - 9;
+        index /= CHANGES;
+    }
+
+    sequence
+}
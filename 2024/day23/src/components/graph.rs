@@ -0,0 +1,197 @@
+use fxhash::FxHashMap;
+use itertools::Itertools;
+
+use super::bitset::BitSet;
+
+/// Undirected graph over interned node names, node `i` is bit `i` of every set.
+#[derive(Debug)]
+pub struct Graph<'a> {
+    names: Vec<&'a str>,
+    adjacency: Vec<BitSet>,
+}
+
+impl<'a> From<&'a str> for Graph<'a> {
+    /// Parses `a-b` edge lines, nodes are numbered in order of appearance.
+    fn from(input: &'a str) -> Self {
+        let mut ids = FxHashMap::default();
+        let mut names = vec![];
+
+        let mut intern = |name: &'a str| {
+            *ids.entry(name).or_insert_with(|| {
+                names.push(name);
+                names.len() - 1
+            })
+        };
+
+        let edges = input
+            .lines()
+            .filter_map(|line| line.split_once('-'))
+            .map(|(a, b)| (intern(a), intern(b)))
+            .collect_vec();
+
+        let mut adjacency = vec![BitSet::new(names.len()); names.len()];
+
+        for (a, b) in edges.into_iter().filter(|(a, b)| a != b) {
+            adjacency[a].insert(b);
+            adjacency[b].insert(a);
+        }
+
+        Graph { names, adjacency }
+    }
+}
+
+impl<'a> Graph<'a> {
+    /// Vertices in smallest-last order along with the graph degeneracy,
+    /// every vertex has at most `degeneracy` neighbours later in the order.
+    pub fn degeneracy_ordering(&self) -> (Vec<usize>, usize) {
+        let mut degree = self.adjacency.iter().map(|n| n.len()).collect_vec();
+        let mut buckets = vec![vec![]; degree.iter().max().map_or(0, |&d| d + 1)];
+        let mut removed = vec![false; self.names.len()];
+        let mut order = Vec::with_capacity(self.names.len());
+        let mut degeneracy = 0;
+        let mut low = 0;
+
+        for (v, &d) in degree.iter().enumerate() {
+            buckets[d].push(v);
+        }
+
+        while order.len() < self.names.len() {
+            // buckets keep stale entries, a vertex is live only at its current degree
+            let v = loop {
+                match buckets[low].pop() {
+                    Some(v) if !removed[v] && degree[v] == low => break v,
+                    Some(_) => continue,
+                    None => low += 1,
+                }
+            };
+
+            degeneracy = degeneracy.max(low);
+            removed[v] = true;
+            order.push(v);
+
+            for u in self.adjacency[v].iter().filter(|&u| !removed[u]) {
+                degree[u] -= 1;
+                buckets[degree[u]].push(u);
+            }
+
+            low = low.saturating_sub(1);
+        }
+
+        (order, degeneracy)
+    }
+
+    /// Every maximal clique as sorted names, Bron–Kerbosch with
+    /// Tomita pivoting run from each vertex in degeneracy order.
+    pub fn maximal_cliques(&self) -> Vec<Vec<&'a str>> {
+        let later = self.later_neighbors();
+        let mut cliques = vec![];
+
+        for (v, p) in later.into_iter().enumerate() {
+            let x = self.adjacency[v].difference(&p);
+
+            self.expand(&mut vec![v], p, x, &mut cliques);
+        }
+
+        cliques
+            .into_iter()
+            .map(|clique| clique.into_iter().map(|v| self.names[v]).sorted().collect())
+            .collect()
+    }
+
+    /// Largest clique as sorted names, ties broken by the smallest listing.
+    pub fn maximum_clique(&self) -> Vec<&'a str> {
+        self.maximal_cliques()
+            .into_iter()
+            .max_by(|a, b| a.len().cmp(&b.len()).then_with(|| b.cmp(a)))
+            .unwrap_or_default()
+    }
+
+    /// Number of cliques with exactly `k` nodes where at least one
+    /// node name starts with `prefix`, each clique counted once.
+    pub fn count_cliques(&self, k: usize, prefix: &str) -> usize {
+        if k == 0 {
+            return 0;
+        }
+
+        let later = self.later_neighbors();
+
+        (0..self.names.len())
+            .map(|v| {
+                let matched = self.names[v].starts_with(prefix);
+                self.count_extensions(k - 1, &later[v], &later, matched, prefix)
+            })
+            .sum()
+    }
+
+    fn expand(&self, r: &mut Vec<usize>, mut p: BitSet, mut x: BitSet, out: &mut Vec<Vec<usize>>) {
+        if p.is_empty() {
+            if x.is_empty() {
+                out.push(r.clone());
+            }
+            return;
+        }
+
+        // branching only on non-neighbours of the pivot still reaches every maximal clique
+        let pivot = p
+            .iter()
+            .chain(x.iter())
+            .max_by_key(|&u| p.intersection(&self.adjacency[u]).len())
+            .unwrap();
+
+        for v in p.difference(&self.adjacency[pivot]).iter().collect_vec() {
+            let neighbors = &self.adjacency[v];
+
+            r.push(v);
+            self.expand(r, p.intersection(neighbors), x.intersection(neighbors), out);
+            r.pop();
+
+            p.remove(v);
+            x.insert(v);
+        }
+    }
+
+    fn count_extensions(
+        &self,
+        k: usize,
+        candidates: &BitSet,
+        later: &[BitSet],
+        matched: bool,
+        prefix: &str,
+    ) -> usize {
+        if k == 0 {
+            return usize::from(matched);
+        }
+
+        if candidates.len() < k {
+            return 0;
+        }
+
+        candidates
+            .iter()
+            .map(|u| {
+                let matched = matched || self.names[u].starts_with(prefix);
+                self.count_extensions(
+                    k - 1,
+                    &candidates.intersection(&later[u]),
+                    later,
+                    matched,
+                    prefix,
+                )
+            })
+            .sum()
+    }
+
+    /// Neighbours of each vertex placed after it in the degeneracy order.
+    fn later_neighbors(&self) -> Vec<BitSet> {
+        let (order, _) = self.degeneracy_ordering();
+        let mut earlier = BitSet::new(self.names.len());
+        let mut later = vec![BitSet::new(self.names.len()); self.names.len()];
+
+        for v in order {
+            earlier.insert(v);
+            later[v] = self.adjacency[v].difference(&earlier);
+        }
+
+        later
+    }
+}
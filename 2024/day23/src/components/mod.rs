@@ -0,0 +1,4 @@
+mod bitset;
+mod graph;
+
+pub use graph::Graph;
@@ -1,10 +1,10 @@
 #![feature(test)]
 
+mod components;
+
 use std::fs;
 
-use cached::proc_macro::cached;
-use fxhash::{FxHashMap, FxHashSet};
-use itertools::Itertools;
+use components::Graph;
 
 fn main() {
     let input = fs::read_to_string("./day23/_data/input.txt").expect("oh noes");
@@ -14,94 +14,11 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    let computers = parse(input);
-
-    let mut groups = FxHashSet::default();
-    for (cpu, conns) in computers.iter() {
-        if !cpu.starts_with('t') {
-            continue;
-        }
-
-        for cpu2 in conns {
-            for cpu3 in computers.get(cpu2).unwrap().intersection(conns) {
-                let mut group = [cpu, cpu2, cpu3];
-                group.sort();
-                groups.insert(group);
-            }
-        }
-    }
-
-    groups.len().to_string()
+    Graph::from(input).count_cliques(3, "t").to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    let computers = parse(input);
-
-    bron_kerbosch(
-        FxHashSet::default(),
-        computers.keys().cloned().collect(),
-        FxHashSet::default(),
-        &computers,
-    )
-    .join(",")
-}
-
-fn parse(input: &str) -> FxHashMap<&str, FxHashSet<&str>> {
-    let mut computers: FxHashMap<_, FxHashSet<_>> = FxHashMap::default();
-    for (cpu1, cpu2) in input.lines().filter_map(|line| line.split_once('-')) {
-        computers
-            .entry(cpu1)
-            .and_modify(|s| {
-                s.insert(cpu2);
-            })
-            .or_insert_with(|| FxHashSet::from_iter([cpu2]));
-
-        computers
-            .entry(cpu2)
-            .and_modify(|s| {
-                s.insert(cpu1);
-            })
-            .or_insert_with(|| FxHashSet::from_iter([cpu1]));
-    }
-    computers
-}
-
-#[cached(
-    key = "(String, String, String, String)",
-    convert = r#"{(format!("{r:?}"), format!("{p:?}"), format!("{x:?}"), format!("{c:p}"))}"#
-)]
-fn bron_kerbosch<'a>(
-    r: FxHashSet<&'a str>,
-    mut p: FxHashSet<&'a str>,
-    mut x: FxHashSet<&'a str>,
-    c: &FxHashMap<&'a str, FxHashSet<&'a str>>,
-) -> Vec<String> {
-    if p.is_empty() && x.is_empty() {
-        return r.into_iter().sorted().map(|s| s.to_owned()).collect_vec();
-    }
-
-    let mut max = vec![];
-    for v in p.clone() {
-        let n = c.get(v).unwrap();
-        let mut r = r.clone();
-        r.insert(v);
-
-        let res = bron_kerbosch(
-            r,
-            p.intersection(n).cloned().collect(),
-            x.intersection(n).cloned().collect(),
-            c,
-        );
-
-        if max.len() < res.len() {
-            max = res;
-        }
-
-        p.remove(v);
-        x.insert(v);
-    }
-
-    max
+    Graph::from(input).maximum_clique().join(",")
 }
 
 #[cfg(test)]
@@ -109,7 +26,10 @@ mod tests {
     extern crate test;
 
     use crate::*;
+    use fxhash::{FxHashMap, FxHashSet};
+    use itertools::Itertools;
     use rstest::{fixture, rstest};
+    use stress::{generate, Rng, StdRng};
     use test::Bencher;
 
     const TEST_CASE: &str = "
@@ -175,6 +95,144 @@ td-yn";
         assert_eq!(expected, process_part_2(&input));
     }
 
+    #[rstest]
+    #[case(1, "", 16)]
+    #[case(2, "", 32)]
+    #[case(3, "", 12)]
+    #[case(3, "t", 7)]
+    #[case(4, "", 1)]
+    #[case(5, "", 0)]
+    #[case(3, "x", 0)]
+    fn count_cliques_check(#[case] k: usize, #[case] prefix: &str, #[case] expected: usize) {
+        assert_eq!(expected, Graph::from(TEST_CASE).count_cliques(k, prefix));
+    }
+
+    #[test]
+    fn maximal_cliques_check() {
+        let graph = Graph::from(TEST_CASE);
+        let computers = parse(TEST_CASE);
+        let cliques = graph.maximal_cliques();
+
+        assert!(cliques.iter().all_unique());
+
+        for clique in cliques.iter() {
+            assert!(clique
+                .iter()
+                .tuple_combinations()
+                .all(|(a, b)| computers[a].contains(b)));
+            assert!(computers
+                .keys()
+                .filter(|name| !clique.contains(name))
+                .all(|name| clique.iter().any(|c| !computers[c].contains(name))));
+        }
+
+        assert_eq!(
+            vec!["co", "de", "ka", "ta"],
+            cliques.into_iter().max_by_key(|c| c.len()).unwrap()
+        );
+    }
+
+    #[test]
+    fn degeneracy_check() {
+        let graph = Graph::from(TEST_CASE);
+        let computers = parse(TEST_CASE);
+        let (order, degeneracy) = graph.degeneracy_ordering();
+
+        assert_eq!(computers.len(), order.iter().unique().count());
+        // every node of the sample has four links, the whole graph is 4-regular
+        assert_eq!(4, degeneracy);
+    }
+
+    stress::register! {
+        generator: generate_network,
+        sizes: [5, 10, 20, 40],
+        part_1_stress: reference_part_1 => process_part_1,
+        part_2_stress: reference_part_2 => process_part_2,
+    }
+
+    /// Sparse random network with a single planted clique that is the largest one.
+    fn generate_network(rng: &mut StdRng, size: usize) -> String {
+        let names = generate::names(rng, size, 2);
+        let clique = 4 + size / 10;
+
+        // outsiders link to at most one clique member, so no other clique can tie
+        let mut attached = FxHashSet::default();
+        let mut links = generate::edges(rng, size, 1.0 / size as f64)
+            .into_iter()
+            .filter(|&(a, b)| b >= clique && (a >= clique || attached.insert(b)))
+            .collect_vec();
+        links.extend((0..clique).tuple_combinations::<(_, _)>());
+
+        links
+            .into_iter()
+            .unique()
+            .map(|(a, b)| {
+                if rng.gen_bool(0.5) {
+                    format!("{}-{}", names[a], names[b])
+                } else {
+                    format!("{}-{}", names[b], names[a])
+                }
+            })
+            .join("\n")
+    }
+
+    fn parse(input: &str) -> FxHashMap<&str, FxHashSet<&str>> {
+        let mut computers: FxHashMap<_, FxHashSet<_>> = FxHashMap::default();
+
+        for (a, b) in input.lines().filter_map(|line| line.split_once('-')) {
+            computers.entry(a).or_default().insert(b);
+            computers.entry(b).or_default().insert(a);
+        }
+
+        computers
+    }
+
+    fn reference_part_1(input: &str) -> String {
+        let computers = parse(input);
+        let names = computers.keys().sorted().collect_vec();
+
+        names
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b, c)| {
+                computers[**a].contains(**b)
+                    && computers[**a].contains(**c)
+                    && computers[**b].contains(**c)
+            })
+            .filter(|(a, b, c)| [a, b, c].iter().any(|n| n.starts_with('t')))
+            .count()
+            .to_string()
+    }
+
+    fn reference_part_2(input: &str) -> String {
+        fn grow<'a>(
+            clique: &mut Vec<&'a str>,
+            rest: &[&'a str],
+            computers: &FxHashMap<&'a str, FxHashSet<&'a str>>,
+            best: &mut Vec<&'a str>,
+        ) {
+            if clique.len() > best.len() {
+                *best = clique.clone();
+            }
+
+            for (i, name) in rest.iter().enumerate() {
+                if clique.iter().all(|c| computers[c].contains(name)) {
+                    clique.push(name);
+                    grow(clique, &rest[i + 1..], computers, best);
+                    clique.pop();
+                }
+            }
+        }
+
+        let computers = parse(input);
+        let names = computers.keys().copied().sorted().collect_vec();
+        let mut best = vec![];
+
+        grow(&mut vec![], &names, &computers, &mut best);
+
+        best.join(",")
+    }
+
     #[bench]
     fn part_1_check_bench(b: &mut Bencher) {
         b.iter(|| process_part_1(TEST_CASE));
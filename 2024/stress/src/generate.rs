@@ -0,0 +1,83 @@
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+/// String of random digits, e.g. a disk map.
+pub fn digits(rng: &mut impl Rng, len: usize, range: RangeInclusive<u32>) -> String {
+    (0..len)
+        .map(|_| char::from_digit(rng.gen_range(range.clone()), 10).unwrap())
+        .collect()
+}
+
+/// Item picked with probability proportional to its weight.
+pub fn weighted<T: Clone>(rng: &mut impl Rng, choices: &[(T, u32)]) -> T {
+    choices
+        .choose_weighted(rng, |(_, weight)| *weight)
+        .map(|(item, _)| item.clone())
+        .unwrap()
+}
+
+/// Grid filled with weighted random tiles.
+pub fn grid(rng: &mut impl Rng, rows: usize, cols: usize, tiles: &[(char, u32)]) -> Vec<Vec<char>> {
+    (0..rows)
+        .map(|_| (0..cols).map(|_| weighted(rng, tiles)).collect())
+        .collect()
+}
+
+/// Places the symbol on a random cell of the grid that holds `on`.
+pub fn place(
+    rng: &mut impl Rng,
+    grid: &mut [Vec<char>],
+    on: char,
+    symbol: char,
+) -> Option<(usize, usize)> {
+    let cells = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(row, line)| {
+            line.iter()
+                .enumerate()
+                .filter(|(_, &ch)| ch == on)
+                .map(move |(col, _)| (row, col))
+        })
+        .collect_vec();
+
+    let &(row, col) = cells.choose(rng)?;
+    grid[row][col] = symbol;
+
+    Some((row, col))
+}
+
+pub fn render_grid(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|line| line.iter().collect::<String>())
+        .join("\n")
+}
+
+/// Distinct lowercase names of the given length.
+pub fn names(rng: &mut impl Rng, count: usize, len: u32) -> Vec<String> {
+    let mut all = (0..26usize.pow(len))
+        .map(|mut n| {
+            (0..len)
+                .map(|_| {
+                    let ch = (b'a' + (n % 26) as u8) as char;
+                    n /= 26;
+                    ch
+                })
+                .collect::<String>()
+        })
+        .collect_vec();
+
+    all.shuffle(rng);
+    all.truncate(count);
+    all
+}
+
+/// Random undirected edges between `nodes` vertices, each present with probability `density`.
+pub fn edges(rng: &mut impl Rng, nodes: usize, density: f64) -> Vec<(usize, usize)> {
+    (0..nodes)
+        .tuple_combinations()
+        .filter(|_| rng.gen_bool(density))
+        .collect()
+}
@@ -0,0 +1,224 @@
+//! Seeded input generators and a runner comparing optimized solutions
+//! against slow reference implementations. A day opts in from its test
+//! module with `register!`, days 03, 06, 09, 22 and 23 do so far.
+
+pub mod generate;
+
+use std::{
+    any::Any,
+    cell::Cell,
+    fmt::{self, Display},
+    panic,
+    sync::Once,
+    thread,
+};
+
+use rand::SeedableRng;
+pub use rand::{rngs::StdRng, Rng};
+
+/// Number of seeds tried per size, can be raised with `STRESS_SEEDS`.
+const DEFAULT_SEEDS: u64 = 20;
+
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    pub expected: String,
+    /// The panic message when the solution panicked.
+    pub actual: Result<String, String>,
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "seed {}, size {}", self.seed, self.size)?;
+        writeln!(f, "expected: {}", self.expected)?;
+        match &self.actual {
+            Ok(actual) => writeln!(f, "actual:   {}", actual)?,
+            Err(message) => writeln!(f, "actual:   <panic: {}>", message)?,
+        }
+        write!(f, "input:\n{}", self.input)
+    }
+}
+
+/// Runs the solution and the reference on generated inputs of increasing size.
+/// Stops at the first size with a mismatch, takes its shortest failing input
+/// and shrinks it by retrying the failing seed on every smaller size down to
+/// the smallest one asked for.
+pub fn check(
+    generator: impl Fn(&mut StdRng, usize) -> String,
+    reference: impl Fn(&str) -> String,
+    solution: impl Fn(&str) -> String + Sync,
+    sizes: impl IntoIterator<Item = usize>,
+) -> Result<(), Failure> {
+    let seeds = std::env::var("STRESS_SEEDS")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_SEEDS);
+
+    let mut sizes = sizes.into_iter().collect::<Vec<_>>();
+    sizes.sort();
+
+    let run = |seed: u64, size: usize| {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let input = generator(&mut rng, size);
+        let expected = reference(&input);
+
+        let actual = isolated(&solution, &input);
+
+        (actual.as_ref() != Ok(&expected)).then_some(Failure {
+            seed,
+            size,
+            input,
+            expected,
+            actual,
+        })
+    };
+
+    for &size in sizes.iter() {
+        let failure = (0..seeds)
+            .filter_map(|seed| run(seed, size))
+            .min_by_key(|f| f.input.len());
+
+        if let Some(failure) = failure {
+            let smaller = (sizes[0]..size).find_map(|size| run(failure.seed, size));
+            return Err(smaller.unwrap_or(failure));
+        }
+    }
+
+    Ok(())
+}
+
+/// Same as `check`, but panics with the shrunk failing input.
+pub fn assert_stress(
+    generator: impl Fn(&mut StdRng, usize) -> String,
+    reference: impl Fn(&str) -> String,
+    solution: impl Fn(&str) -> String + Sync,
+    sizes: impl IntoIterator<Item = usize>,
+) {
+    if let Err(failure) = check(generator, reference, solution, sizes) {
+        panic!("stress check failed for {}", failure);
+    }
+}
+
+/// Registers stress tests for a day, one `#[test]` per part checking the
+/// solution against the reference on inputs of every size.
+///
+/// ```text
+/// stress::register! {
+///     generator: generate_lab,
+///     sizes: [2, 5, 10, 20],
+///     part_1_stress: reference_part_1 => process_part_1,
+///     part_2_stress: reference_part_2 => process_part_2,
+/// }
+/// ```
+#[macro_export]
+macro_rules! register {
+    (
+        generator: $generator:expr,
+        sizes: $sizes:expr,
+        $($name:ident: $reference:expr => $solution:expr),+ $(,)?
+    ) => {
+        $(
+            #[test]
+            fn $name() {
+                $crate::assert_stress($generator, $reference, $solution, $sizes);
+            }
+        )+
+    };
+}
+
+thread_local! {
+    /// Set on the threads running a solution, their panics are expected.
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+}
+
+static QUIET_HOOK: Once = Once::new();
+
+/// Runs the solution on a thread of its own and hands back its panic message
+/// instead of unwinding. The panic hook is wrapped once so panics on that
+/// thread aren't printed while shrinking, every other thread still reports
+/// through the hook that was there before, worker threads the solution
+/// spawns included.
+fn isolated(solution: &(impl Fn(&str) -> String + Sync), input: &str) -> Result<String, String> {
+    QUIET_HOOK.call_once(|| {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !ISOLATED.get() {
+                hook(info);
+            }
+        }));
+    });
+
+    thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                ISOLATED.set(true);
+                solution(input)
+            })
+            .join()
+            .map_err(panic_message)
+    })
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload
+            .downcast_ref::<&str>()
+            .map_or_else(|| "<non-string payload>".to_owned(), |s| s.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn passing_check() {
+        let res = check(
+            |rng, size| generate::digits(rng, size, 0..=9),
+            |input| input.chars().rev().collect(),
+            |input| input.chars().rev().collect(),
+            [1, 4, 16],
+        );
+
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn failing_check() {
+        let res = check(
+            |rng, size| generate::digits(rng, size, 0..=9),
+            |input| input.len().to_string(),
+            |input| {
+                if input.contains('7') {
+                    panic!("sevens are not allowed")
+                }
+                input.len().to_string()
+            },
+            [64, 2, 8],
+        );
+
+        let failure = res.unwrap_err();
+        assert_eq!(2, failure.size);
+        assert!(failure.input.contains('7'));
+        assert_eq!(Err("sevens are not allowed".to_owned()), failure.actual);
+    }
+
+    #[test]
+    fn shrink_check() {
+        // only inputs of 5 digits or more fail, the first size tried failing is 8
+        let res = check(
+            |rng, size| generate::digits(rng, size, 0..=9),
+            |input| input.len().to_string(),
+            |input| (input.len() % 5).to_string(),
+            [64, 1, 8],
+        );
+
+        let failure = res.unwrap_err();
+        assert_eq!(5, failure.size);
+        assert_eq!(5, failure.input.len());
+        assert_eq!(Ok("0".to_owned()), failure.actual);
+    }
+}
@@ -1,8 +1,9 @@
 #![feature(test)]
 
-use std::{fs, ops::RangeInclusive};
+use std::fs;
 
 use itertools::Itertools;
+use rangeset::RangeSet;
 
 fn main() {
     let input = fs::read_to_string("./crates/day05/_data/input.txt").expect("oh noes");
@@ -16,7 +17,7 @@ fn process_part_1(input: &str) -> String {
 
     ingredients
         .into_iter()
-        .filter(|i| ranges.iter().any(|r| r.contains(i)))
+        .filter(|&i| ranges.contains(i))
         .count()
         .to_string()
 }
@@ -24,25 +25,21 @@ fn process_part_1(input: &str) -> String {
 fn process_part_2(input: &str) -> String {
     let (ranges, _) = parse(input);
 
-    ranges
-        .into_iter()
-        .map(|r| r.count())
-        .sum::<usize>()
-        .to_string()
+    ranges.len().to_string()
 }
 
-fn parse(input: &str) -> (Vec<RangeInclusive<u64>>, Vec<u64>) {
+fn parse(input: &str) -> (RangeSet<u64>, Vec<u64>) {
     let mut lines = input.lines().skip_while(|line| line.is_empty());
     let lines = lines.by_ref();
 
-    let ranges = lines
-        .take_while(|line| !line.is_empty())
-        .filter_map(|line| line.split_once('-'))
-        .filter_map(|(a, b)| a.parse::<u64>().ok().zip(b.parse::<u64>().ok()))
-        .map(|(a, b)| (a..=b))
-        .collect_vec();
-
-    let ranges = reduce_ranges(ranges);
+    let ranges = RangeSet::try_from_inclusive(
+        lines
+            .take_while(|line| !line.is_empty())
+            .filter_map(|line| line.split_once('-'))
+            .filter_map(|(a, b)| a.parse::<u64>().ok().zip(b.parse::<u64>().ok()))
+            .map(|(a, b)| a..=b),
+    )
+    .unwrap();
 
     let ingredients = lines
         .filter_map(|line| line.parse::<u64>().ok())
@@ -51,22 +48,6 @@ fn parse(input: &str) -> (Vec<RangeInclusive<u64>>, Vec<u64>) {
     (ranges, ingredients)
 }
 
-fn reduce_ranges(mut ranges: Vec<RangeInclusive<u64>>) -> Vec<RangeInclusive<u64>> {
-    let mut res = vec![];
-
-    while let Some(item) = ranges.pop() {
-        match ranges
-            .iter_mut()
-            .find(|r| r.end() >= item.start() && r.start() <= item.end())
-        {
-            Some(r) => *r = *r.start().min(item.start())..=*r.end().max(item.end()),
-            None => res.push(item),
-        }
-    }
-
-    res
-}
-
 #[cfg(test)]
 mod tests {
     extern crate test;
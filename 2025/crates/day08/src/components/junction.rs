@@ -0,0 +1,36 @@
+use itertools::Itertools;
+
+#[derive(Clone, Hash, PartialEq, Eq, Debug)]
+pub struct Junction {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+
+impl TryFrom<&str> for Junction {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value
+            .split(',')
+            .filter_map(|p| p.parse().ok())
+            .next_tuple()
+            .map(|(x, y, z)| Junction { x, y, z })
+            .ok_or(value.to_owned())
+    }
+}
+
+impl Junction {
+    pub fn coords(&self) -> [usize; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    /// Squared euclidean distance, exact and ordered the same as the real one.
+    pub fn distance_sq(&self, other: &Junction) -> usize {
+        self.coords()
+            .iter()
+            .zip(other.coords())
+            .map(|(a, b)| a.abs_diff(b).pow(2))
+            .sum()
+    }
+}
@@ -0,0 +1,146 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use super::Junction;
+
+const INITIAL_BATCH: usize = 4;
+
+/// Implicit k-d tree, subtree `order[lo..hi]` splits on its middle element
+/// along axis `depth % 3`.
+pub struct KdTree<'a> {
+    points: &'a [Junction],
+    order: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    pub fn new(points: &'a [Junction]) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        build(points, &mut order, 0);
+
+        KdTree { points, order }
+    }
+
+    /// `k` nearest other points as `(distance_sq, index)`, ascending, ties by index.
+    pub fn nearest(&self, index: usize, k: usize) -> Vec<(usize, usize)> {
+        let mut best = BinaryHeap::with_capacity(k + 1);
+        self.search(index, k, 0, self.order.len(), 0, &mut best);
+
+        best.into_sorted_vec()
+    }
+
+    /// All pairs `(a, b, distance_sq)` with `a < b`, in increasing distance
+    /// and then index order, produced lazily.
+    pub fn closest_pairs(&self) -> ClosestPairs<'_, 'a> {
+        let mut pairs = ClosestPairs {
+            tree: self,
+            neighbors: vec![vec![]; self.points.len()],
+            cursors: vec![0; self.points.len()],
+            heap: BinaryHeap::new(),
+        };
+
+        for index in 0..self.points.len() {
+            pairs.neighbors[index] = self.nearest(index, INITIAL_BATCH);
+            pairs.push_next(index);
+        }
+
+        pairs
+    }
+
+    fn search(
+        &self,
+        index: usize,
+        k: usize,
+        lo: usize,
+        hi: usize,
+        depth: usize,
+        best: &mut BinaryHeap<(usize, usize)>,
+    ) {
+        if lo >= hi || k == 0 {
+            return;
+        }
+
+        let mid = (lo + hi) / 2;
+        let node = self.order[mid];
+        let query = &self.points[index];
+
+        if node != index {
+            let candidate = (query.distance_sq(&self.points[node]), node);
+
+            if best.len() < k {
+                best.push(candidate);
+            } else if best.peek().is_some_and(|worst| candidate < *worst) {
+                best.pop();
+                best.push(candidate);
+            }
+        }
+
+        let axis = depth % 3;
+        let (q, n) = (query.coords()[axis], self.points[node].coords()[axis]);
+        let (near, far) = if q < n {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.search(index, k, near.0, near.1, depth + 1, best);
+
+        let plane = q.abs_diff(n).pow(2);
+        if best.len() < k || best.peek().is_some_and(|worst| plane <= worst.0) {
+            self.search(index, k, far.0, far.1, depth + 1, best);
+        }
+    }
+}
+
+fn build(points: &[Junction], order: &mut [usize], depth: usize) {
+    if order.len() <= 1 {
+        return;
+    }
+
+    let axis = depth % 3;
+    let mid = order.len() / 2;
+    order.select_nth_unstable_by_key(mid, |&i| points[i].coords()[axis]);
+
+    let (left, right) = order.split_at_mut(mid);
+    build(points, left, depth + 1);
+    build(points, &mut right[1..], depth + 1);
+}
+
+pub struct ClosestPairs<'t, 'a> {
+    tree: &'t KdTree<'a>,
+    neighbors: Vec<Vec<(usize, usize)>>,
+    cursors: Vec<usize>,
+    heap: BinaryHeap<Reverse<(usize, usize, usize)>>,
+}
+
+impl ClosestPairs<'_, '_> {
+    fn push_next(&mut self, index: usize) {
+        let cursor = self.cursors[index];
+        let fetched = self.neighbors[index].len();
+
+        // the batch was full, so there may be more neighbors further away
+        if cursor == fetched && fetched > 0 && fetched < self.tree.points.len() - 1 {
+            self.neighbors[index] = self.tree.nearest(index, fetched * 2);
+        }
+
+        if let Some(&(dist, other)) = self.neighbors[index].get(cursor) {
+            self.heap.push(Reverse((dist, index, other)));
+        }
+    }
+}
+
+impl Iterator for ClosestPairs<'_, '_> {
+    type Item = (usize, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(Reverse((dist, index, other))) = self.heap.pop() {
+            self.cursors[index] += 1;
+            self.push_next(index);
+
+            // every pair is seen from both ends, keep the one from the smaller index
+            if index < other {
+                return Some((index, other, dist));
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,5 @@
+mod junction;
+mod kd_tree;
+
+pub use junction::Junction;
+pub use kd_tree::KdTree;
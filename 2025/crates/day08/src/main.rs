@@ -1,11 +1,14 @@
 #![feature(test)]
 
+mod components;
+
 use std::fs;
 
 use itertools::Itertools;
-use num::integer::Roots;
 use partitions::PartitionVec;
 
+use components::{Junction, KdTree};
+
 fn main() {
     let input = fs::read_to_string("./crates/day08/_data/input.txt").expect("oh noes");
 
@@ -14,20 +17,14 @@ fn main() {
 }
 
 fn process_part_1(input: &str, count: usize) -> String {
-    let junctions: Vec<Junction> = input
-        .lines()
-        .filter_map(|line| line.try_into().ok())
-        .collect_vec();
+    let junctions = parse(input);
 
     let mut sets = PartitionVec::from_iter(junctions.iter());
 
-    junctions
-        .iter()
-        .enumerate()
-        .tuple_combinations()
-        .sorted_by_key(|(a, b)| a.1.distance(b.1))
+    KdTree::new(&junctions)
+        .closest_pairs()
         .take(count)
-        .for_each(|(a, b)| sets.union(a.0, b.0));
+        .for_each(|(a, b, _)| sets.union(a, b));
 
     sets.all_sets()
         .map(|s| s.count())
@@ -39,57 +36,26 @@ fn process_part_1(input: &str, count: usize) -> String {
 }
 
 fn process_part_2(input: &str) -> String {
-    let junctions: Vec<Junction> = input
-        .lines()
-        .filter_map(|line| line.try_into().ok())
-        .collect_vec();
+    let junctions = parse(input);
 
     let mut sets = PartitionVec::from_iter(junctions.iter());
 
-    for (a, b) in junctions
-        .iter()
-        .enumerate()
-        .tuple_combinations()
-        .sorted_by_key(|(a, b)| a.1.distance(b.1))
-    {
-        sets.union(a.0, b.0);
+    for (a, b, _) in KdTree::new(&junctions).closest_pairs() {
+        sets.union(a, b);
 
         if sets.amount_of_sets() == 1 {
-            return (a.1.x * b.1.x).to_string();
+            return (junctions[a].x * junctions[b].x).to_string();
         }
     }
 
     unreachable!()
 }
 
-#[derive(Clone, Hash, PartialEq, Eq, Debug)]
-struct Junction {
-    x: usize,
-    y: usize,
-    z: usize,
-}
-
-impl TryFrom<&str> for Junction {
-    type Error = String;
-
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        value
-            .split(',')
-            .filter_map(|p| p.parse().ok())
-            .next_tuple()
-            .map(|(x, y, z)| Junction { x, y, z })
-            .ok_or(value.to_owned())
-    }
-}
-
-impl Junction {
-    fn distance(&self, other: &Junction) -> usize {
-        let x = self.x.abs_diff(other.x);
-        let y = self.y.abs_diff(other.y);
-        let z = self.z.abs_diff(other.z);
-
-        (x * x + y * y + z * z).sqrt()
-    }
+fn parse(input: &str) -> Vec<Junction> {
+    input
+        .lines()
+        .filter_map(|line| line.try_into().ok())
+        .collect_vec()
 }
 
 #[cfg(test)]
@@ -97,6 +63,7 @@ mod tests {
     extern crate test;
 
     use crate::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
     use rstest::{fixture, rstest};
     use test::Bencher;
 
@@ -139,6 +106,54 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    #[rstest]
+    #[case(TEST_CASE)]
+    #[case(&random_junctions(300, 20))]
+    #[case(&random_junctions(200, 1_000_000))]
+    fn closest_pairs_check(#[case] input: &str) {
+        let junctions = parse(input);
+
+        let expected = junctions
+            .iter()
+            .enumerate()
+            .tuple_combinations()
+            .map(|(a, b)| (a.0, b.0, a.1.distance_sq(b.1)))
+            .sorted_by_key(|(a, b, dist)| (*dist, *a, *b))
+            .collect_vec();
+
+        let actual = KdTree::new(&junctions).closest_pairs().collect_vec();
+
+        assert_eq!(expected, actual);
+    }
+
+    #[rstest]
+    #[case(TEST_CASE, 0, 3)]
+    #[case(TEST_CASE, 7, 19)]
+    fn nearest_check(#[case] input: &str, #[case] index: usize, #[case] k: usize) {
+        let junctions = parse(input);
+
+        let expected = (0..junctions.len())
+            .filter(|&i| i != index)
+            .map(|i| (junctions[index].distance_sq(&junctions[i]), i))
+            .sorted()
+            .take(k)
+            .collect_vec();
+
+        assert_eq!(expected, KdTree::new(&junctions).nearest(index, k));
+    }
+
+    fn random_junctions(count: usize, max: usize) -> String {
+        let mut rng = StdRng::seed_from_u64(count as u64);
+
+        (0..count)
+            .map(|_| {
+                (0..3)
+                    .map(|_| rng.random_range(0..max).to_string())
+                    .join(",")
+            })
+            .join("\n")
+    }
+
     #[rstest]
     #[case("181584")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -1,8 +1,5 @@
-mod direction;
-mod field;
-mod orientation;
-mod oriented_point;
 mod point;
-mod vector;
+mod polygon;
 
-pub use field::Field;
+pub use point::Point;
+pub use polygon::Polygon;
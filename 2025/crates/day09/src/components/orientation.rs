@@ -1,5 +0,0 @@
-#[derive(Clone, Copy, PartialEq)]
-pub enum Orientation {
-    Clockwise,
-    Counter,
-}
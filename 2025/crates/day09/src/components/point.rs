@@ -1,6 +1,4 @@
-use std::ops::Sub;
-
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Hash, PartialEq, Eq, Debug)]
 pub struct Point {
     pub x: isize,
     pub y: isize,
@@ -18,23 +16,8 @@ impl TryFrom<&str> for Point {
     }
 }
 
-impl Sub for Point {
-    type Output = Self;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Self {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
-}
-
 impl Point {
     pub fn get_square_area_with(&self, other: &Point) -> usize {
         (self.x.abs_diff(other.x) + 1) * (self.y.abs_diff(other.y) + 1)
     }
-
-    pub fn coordinates_signums(&self) -> (isize, isize) {
-        (self.x.signum(), self.y.signum())
-    }
 }
@@ -0,0 +1,149 @@
+use std::collections::VecDeque;
+
+use itertools::Itertools;
+
+use super::Point;
+
+/// Simple orthogonal polygon over tile coordinates,
+/// tiles on the boundary count as inside.
+///
+/// Coordinates are compressed per axis: for sorted distinct values `v`,
+/// index `2i + 1` is the line `v[i]` and even indices are the open gaps
+/// around them, including one outer gap on each side.
+pub struct Polygon {
+    vertices: Vec<Point>,
+    xs: Vec<isize>,
+    ys: Vec<isize>,
+    inside: Vec<Vec<bool>>,
+    /// `outside_sums[r][c]` counts outside cells holding tiles above and left of `(r, c)`.
+    outside_sums: Vec<Vec<usize>>,
+}
+
+impl TryFrom<Vec<Point>> for Polygon {
+    type Error = String;
+
+    fn try_from(vertices: Vec<Point>) -> Result<Self, Self::Error> {
+        if vertices.len() < 4 {
+            return Err(format!("Too few vertices: {}", vertices.len()));
+        }
+
+        if let Some((a, b)) = vertices
+            .iter()
+            .circular_tuple_windows()
+            .find(|(a, b)| a.x != b.x && a.y != b.y)
+        {
+            return Err(format!("Edge {:?} -> {:?} is not axis aligned", a, b));
+        }
+
+        let xs = vertices.iter().map(|p| p.x).sorted().dedup().collect_vec();
+        let ys = vertices.iter().map(|p| p.y).sorted().dedup().collect_vec();
+
+        let mut polygon = Polygon {
+            vertices,
+            inside: vec![vec![true; xs.len() * 2 + 1]; ys.len() * 2 + 1],
+            outside_sums: vec![],
+            xs,
+            ys,
+        };
+
+        polygon.fill_outside();
+        polygon.build_sums();
+
+        Ok(polygon)
+    }
+}
+
+impl Polygon {
+    /// Largest rectangle with opposite corners on two vertices
+    /// and every tile inside the polygon.
+    pub fn largest_inner_rectangle(&self) -> Option<usize> {
+        self.vertices
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| self.is_inside(a, b))
+            .map(|(a, b)| a.get_square_area_with(b))
+            .max()
+    }
+
+    /// Whether every tile of the rectangle spanned by `a` and `b` is inside.
+    pub fn is_inside(&self, a: &Point, b: &Point) -> bool {
+        let (r1, r2) = minmax(compress(&self.ys, a.y), compress(&self.ys, b.y));
+        let (c1, c2) = minmax(compress(&self.xs, a.x), compress(&self.xs, b.x));
+
+        let sums = &self.outside_sums;
+        sums[r2 + 1][c2 + 1] + sums[r1][c1] == sums[r1][c2 + 1] + sums[r2 + 1][c1]
+    }
+
+    fn fill_outside(&mut self) {
+        let rows = self.inside.len();
+        let cols = self.inside[0].len();
+        let mut boundary = vec![vec![false; cols]; rows];
+
+        for (a, b) in self.vertices.iter().circular_tuple_windows() {
+            let (r1, r2) = minmax(compress(&self.ys, a.y), compress(&self.ys, b.y));
+            let (c1, c2) = minmax(compress(&self.xs, a.x), compress(&self.xs, b.x));
+
+            for row in boundary.iter_mut().take(r2 + 1).skip(r1) {
+                row[c1..=c2].fill(true);
+            }
+        }
+
+        // the outer gaps are always outside and connect the whole exterior
+        let mut queue = VecDeque::from([(0usize, 0usize)]);
+        self.inside[0][0] = false;
+
+        while let Some((row, col)) = queue.pop_front() {
+            let neighbors = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+
+            for (r, c) in neighbors {
+                if r < rows && c < cols && !boundary[r][c] && self.inside[r][c] {
+                    self.inside[r][c] = false;
+                    queue.push_back((r, c));
+                }
+            }
+        }
+    }
+
+    fn build_sums(&mut self) {
+        let rows = self.inside.len();
+        let cols = self.inside[0].len();
+        let mut sums = vec![vec![0; cols + 1]; rows + 1];
+
+        for r in 0..rows {
+            for c in 0..cols {
+                // gaps between adjacent coordinates hold no tiles and never block
+                let blocking =
+                    !self.inside[r][c] && has_tiles(&self.ys, r) && has_tiles(&self.xs, c);
+
+                sums[r + 1][c + 1] =
+                    sums[r][c + 1] + sums[r + 1][c] - sums[r][c] + usize::from(blocking);
+            }
+        }
+
+        self.outside_sums = sums;
+    }
+}
+
+fn compress(values: &[isize], value: isize) -> usize {
+    match values.binary_search(&value) {
+        Ok(i) => 2 * i + 1,
+        Err(i) => 2 * i,
+    }
+}
+
+fn has_tiles(values: &[isize], index: usize) -> bool {
+    if index % 2 == 1 || index == 0 || index == values.len() * 2 {
+        true
+    } else {
+        values[index / 2] - values[index / 2 - 1] > 1
+    }
+}
+
+fn minmax(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
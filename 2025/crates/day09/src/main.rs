@@ -6,7 +6,7 @@ use std::fs;
 
 use itertools::Itertools;
 
-use components::Field;
+use components::{Point, Polygon};
 
 fn main() {
     let input = fs::read_to_string("./crates/day09/_data/input.txt").expect("oh noes");
@@ -16,23 +16,30 @@ fn main() {
 }
 
 fn process_part_1(input: &str) -> String {
-    input
-        .lines()
-        .filter_map(|line| line.split_once(','))
-        .filter_map(|(a, b)| a.parse::<usize>().ok().zip(b.parse::<usize>().ok()))
+    parse(input)
+        .iter()
         .tuple_combinations()
-        .map(|(a, b)| (a.0.abs_diff(b.0) + 1) * (a.1.abs_diff(b.1) + 1))
+        .map(|(a, b)| a.get_square_area_with(b))
         .max()
         .unwrap()
         .to_string()
 }
 
 fn process_part_2(input: &str) -> String {
-    Field::from(input)
-        .get_largest_inner_square_area()
+    Polygon::try_from(parse(input))
+        .unwrap()
+        .largest_inner_rectangle()
+        .unwrap()
         .to_string()
 }
 
+fn parse(input: &str) -> Vec<Point> {
+    input
+        .lines()
+        .filter_map(|line| line.try_into().ok())
+        .collect_vec()
+}
+
 #[cfg(test)]
 mod tests {
     extern crate test;
@@ -68,6 +75,114 @@ mod tests {
         assert_eq!(expected, process_part_2(input));
     }
 
+    // U shape opening upwards
+    const TEST_CASE_U: &str = "
+0,0
+10,0
+10,10
+7,10
+7,3
+3,3
+3,10
+0,10";
+
+    // two lobes joined by a one tile wide corridor, with a zero width outside notch
+    const TEST_CASE_NOTCH: &str = "
+0,0
+4,0
+4,2
+5,2
+5,0
+9,0
+9,6
+0,6";
+
+    // outline winding the other way with collinear vertices
+    const TEST_CASE_CCW: &str = "
+0,0
+0,8
+6,8
+6,5
+6,4
+12,4
+12,0
+5,0";
+
+    #[rstest]
+    #[case(TEST_CASE, 24)]
+    #[case(TEST_CASE_U, 44)]
+    #[case(TEST_CASE_NOTCH, 70)]
+    #[case(TEST_CASE_CCW, 65)]
+    fn polygon_check(#[case] input: &str, #[case] expected: usize) {
+        let points = parse(input);
+        let polygon = Polygon::try_from(points.clone()).unwrap();
+
+        assert_eq!(Some(expected), polygon.largest_inner_rectangle());
+        assert_eq!(Some(expected), brute_force_rectangle(&points));
+    }
+
+    #[rstest]
+    #[case(TEST_CASE)]
+    #[case(TEST_CASE_U)]
+    #[case(TEST_CASE_NOTCH)]
+    #[case(TEST_CASE_CCW)]
+    fn contains_check(#[case] input: &str) {
+        let points = parse(input);
+        let polygon = Polygon::try_from(points.clone()).unwrap();
+
+        for x in -1..=14 {
+            for y in -1..=12 {
+                let p = Point { x, y };
+                assert_eq!(
+                    brute_force_contains(&points, &p),
+                    polygon.is_inside(&p, &p),
+                    "{:?}",
+                    p
+                );
+            }
+        }
+    }
+
+    #[rstest]
+    #[case("0,0\n5,5\n0,5")]
+    #[case("0,0\n5,0")]
+    fn invalid_polygon_check(#[case] input: &str) {
+        assert!(Polygon::try_from(parse(input)).is_err());
+    }
+
+    fn brute_force_contains(points: &[Point], p: &Point) -> bool {
+        let mut crossings = 0;
+
+        for (a, b) in points.iter().circular_tuple_windows() {
+            let on_edge = (a.x.min(b.x)..=a.x.max(b.x)).contains(&p.x)
+                && (a.y.min(b.y)..=a.y.max(b.y)).contains(&p.y);
+
+            if on_edge {
+                return true;
+            }
+
+            // ray to the right, vertical edges counted half-open
+            if a.x == b.x && a.x > p.x && (a.y.min(b.y)..a.y.max(b.y)).contains(&p.y) {
+                crossings += 1;
+            }
+        }
+
+        crossings % 2 == 1
+    }
+
+    fn brute_force_rectangle(points: &[Point]) -> Option<usize> {
+        points
+            .iter()
+            .tuple_combinations()
+            .filter(|(a, b)| {
+                (a.x.min(b.x)..=a.x.max(b.x))
+                    .cartesian_product(a.y.min(b.y)..=a.y.max(b.y))
+                    .all(|(x, y)| brute_force_contains(points, &Point { x, y }))
+            })
+            .map(|(a, b)| a.get_square_area_with(b))
+            .max()
+    }
+
     #[rstest]
     #[case("4771532800")]
     fn part_1_control(input: String, #[case] expected: &str) {
@@ -0,0 +1,3 @@
+mod polygon;
+
+pub use polygon::{Polygon, Winding};
@@ -0,0 +1,158 @@
+use num::{Integer, Signed};
+
+/// Turning direction of a polygon, with `x` growing to the right and `y` upwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Winding {
+    CounterClockwise,
+    Clockwise,
+    /// No area at all, every vertex on one line.
+    Degenerate,
+}
+
+/// Simple polygon on the integer lattice, vertices as `(x, y)` in order,
+/// the last one joined back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon<T> {
+    vertices: Vec<(T, T)>,
+}
+
+impl<T: Signed + Integer + Copy> FromIterator<(T, T)> for Polygon<T> {
+    fn from_iter<I: IntoIterator<Item = (T, T)>>(iter: I) -> Self {
+        Polygon {
+            vertices: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: Signed + Integer + Copy> Polygon<T> {
+    pub fn new(vertices: Vec<(T, T)>) -> Self {
+        Polygon { vertices }
+    }
+
+    /// Polygon traced from the origin by consecutive `(dx, dy)` moves,
+    /// as a dig plan or a path of pipes would walk it.
+    pub fn from_moves(moves: impl IntoIterator<Item = (T, T)>) -> Self {
+        moves
+            .into_iter()
+            .scan((T::zero(), T::zero()), |(x, y), (dx, dy)| {
+                (*x, *y) = (*x + dx, *y + dy);
+                Some((*x, *y))
+            })
+            .collect()
+    }
+
+    pub fn vertices(&self) -> &[(T, T)] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((T, T), (T, T))> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .zip(self.vertices.iter().copied().cycle().skip(1))
+    }
+
+    /// Twice the signed shoelace area, kept doubled so it stays an integer.
+    /// Positive for counter-clockwise polygons.
+    pub fn double_signed_area(&self) -> T {
+        self.edges()
+            .fold(T::zero(), |acc, (a, b)| acc + a.0 * b.1 - b.0 * a.1)
+    }
+
+    pub fn double_area(&self) -> T {
+        self.double_signed_area().abs()
+    }
+
+    pub fn winding(&self) -> Winding {
+        match self.double_signed_area() {
+            a if a > T::zero() => Winding::CounterClockwise,
+            a if a < T::zero() => Winding::Clockwise,
+            _ => Winding::Degenerate,
+        }
+    }
+
+    /// Lattice points on the edges, an edge covering `gcd(|dx|, |dy|)` of them
+    /// besides its starting vertex.
+    pub fn boundary_points(&self) -> T {
+        self.edges()
+            .fold(T::zero(), |acc, (a, b)| acc + (b.0 - a.0).gcd(&(b.1 - a.1)))
+    }
+
+    /// Lattice points strictly inside, by Pick's theorem `A = I + B / 2 - 1`.
+    /// Polygons without area, fewer than three vertices or all on one line,
+    /// have none.
+    pub fn interior_points(&self) -> T {
+        if self.vertices.len() < 3 || self.winding() == Winding::Degenerate {
+            return T::zero();
+        }
+
+        let two = T::one() + T::one();
+        (self.double_area() - self.boundary_points() + two) / two
+    }
+
+    /// Lattice points inside or on the boundary, the cells a trench dug
+    /// along the edges encloses together with the trench itself.
+    pub fn lattice_points(&self) -> T {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square_check() {
+        let square = Polygon::new(vec![(0i64, 0), (4, 0), (4, 4), (0, 4)]);
+
+        assert_eq!(32, square.double_signed_area());
+        assert_eq!(Winding::CounterClockwise, square.winding());
+        assert_eq!(16, square.boundary_points());
+        assert_eq!(9, square.interior_points());
+        assert_eq!(25, square.lattice_points());
+
+        let reversed = Polygon::from_iter(square.vertices().iter().rev().copied());
+        assert_eq!(-32, reversed.double_signed_area());
+        assert_eq!(Winding::Clockwise, reversed.winding());
+        assert_eq!(9, reversed.interior_points());
+    }
+
+    #[test]
+    fn slanted_check() {
+        // lattice triangle, points counted by hand
+        let triangle = Polygon::new(vec![(0i32, 0), (6, 0), (0, 4)]);
+
+        assert_eq!(24, triangle.double_area());
+        assert_eq!(6 + 4 + 2, triangle.boundary_points());
+        assert_eq!(7, triangle.interior_points());
+    }
+
+    #[test]
+    fn degenerate_check() {
+        let line = Polygon::new(vec![(0i32, 0), (3, 3), (6, 6)]);
+        assert_eq!(Winding::Degenerate, line.winding());
+        assert_eq!(0, line.interior_points());
+
+        assert_eq!(0, Polygon::<i64>::new(vec![]).interior_points());
+        assert_eq!(0, Polygon::new(vec![(2i64, 3)]).interior_points());
+        assert_eq!(0, Polygon::new(vec![(0i64, 0), (4, 0)]).interior_points());
+    }
+
+    #[test]
+    fn moves_check() {
+        let l_shape = Polygon::from_moves([(3i64, 0), (0, 1), (-2, 0), (0, 2), (-1, 0), (0, -3)]);
+
+        assert_eq!(&(0, 0), l_shape.vertices().last().unwrap());
+        assert_eq!(5 * 2, l_shape.double_area());
+        assert_eq!(12, l_shape.boundary_points());
+        assert_eq!(12, l_shape.lattice_points());
+    }
+
+    #[test]
+    fn wide_check() {
+        let big = 3_000_000_000i128;
+        let square = Polygon::new(vec![(0, 0), (big, 0), (big, big), (0, big)]);
+
+        assert_eq!((big + 1) * (big + 1), square.lattice_points());
+    }
+}
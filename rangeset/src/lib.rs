@@ -0,0 +1,9 @@
+mod coord;
+mod offset_map;
+mod range_box;
+mod range_set;
+
+pub use coord::Coord;
+pub use offset_map::OffsetMap;
+pub use range_box::{BoxUnion, RangeBox};
+pub use range_set::RangeSet;
@@ -0,0 +1,94 @@
+use std::ops::Range;
+
+use crate::{Coord, RangeSet};
+
+/// Piecewise-linear map, values inside a source range move by the same
+/// offset as its start, everything else maps to itself.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetMap<T> {
+    /// `(source, destination start)` sorted by source, sources never overlap.
+    entries: Vec<(Range<T>, T)>,
+}
+
+impl<T: Coord> OffsetMap<T> {
+    /// Builds the map from `(source, destination start)` pairs,
+    /// `None` if two source ranges overlap.
+    pub fn new(entries: impl IntoIterator<Item = (Range<T>, T)>) -> Option<Self> {
+        let mut entries = entries
+            .into_iter()
+            .filter(|(r, _)| r.start < r.end)
+            .collect::<Vec<_>>();
+        entries.sort_unstable_by_key(|(r, _)| r.start);
+
+        if entries.windows(2).any(|w| w[0].0.end > w[1].0.start) {
+            return None;
+        }
+
+        Some(OffsetMap { entries })
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let i = self.entries.partition_point(|(r, _)| r.end <= value);
+
+        match self.entries.get(i) {
+            Some((source, dest)) if source.start <= value => value - source.start + *dest,
+            _ => value,
+        }
+    }
+
+    /// Image of a whole set, each source piece shifted on its own.
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut rest = set.clone();
+        let mut mapped = vec![];
+
+        for (source, dest) in self.entries.iter() {
+            let source = RangeSet::from(source.clone());
+            let hit = set.intersection(&source);
+
+            rest = rest.difference(&source);
+            mapped.push(hit.shift(*dest, source.min().unwrap()));
+        }
+
+        mapped
+            .iter()
+            .flat_map(|s| s.ranges().iter().cloned())
+            .chain(rest.ranges().iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_check() {
+        let map = OffsetMap::new([(98..100u64, 50), (50..98, 52)]).unwrap();
+
+        assert_eq!(81, map.map(79));
+        assert_eq!(14, map.map(14));
+        assert_eq!(57, map.map(55));
+        assert_eq!(50, map.map(98));
+        assert_eq!(100, map.map(100));
+    }
+
+    #[test]
+    fn map_set_check() {
+        let map = OffsetMap::new([(98..100u64, 50), (50..98, 52), (10..20, 0)]).unwrap();
+        let set = RangeSet::from_iter([5..15u64, 45..100]);
+        let mapped = map.map_set(&set);
+
+        let expected = (0..120)
+            .filter(|&v| set.contains(v))
+            .map(|v| map.map(v)..map.map(v) + 1)
+            .collect::<RangeSet<_>>();
+
+        assert_eq!(expected.len(), mapped.len());
+        assert!((0..120).all(|v| expected.contains(v) == mapped.contains(v)));
+    }
+
+    #[test]
+    fn overlap_check() {
+        assert!(OffsetMap::new([(0..10, 5), (9..12, 0)]).is_none());
+    }
+}
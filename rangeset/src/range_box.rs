@@ -0,0 +1,267 @@
+use std::ops::Range;
+
+use crate::Coord;
+
+/// Axis aligned box made of one half-open range per dimension.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeBox<T, const N: usize> {
+    pub ranges: [Range<T>; N],
+}
+
+impl<T: Coord, const N: usize> RangeBox<T, N> {
+    pub fn new(ranges: [Range<T>; N]) -> Self {
+        RangeBox { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().any(|r| r.start >= r.end)
+    }
+
+    /// Number of lattice points inside, `T` has to be wide enough for the product.
+    pub fn volume(&self) -> T {
+        if self.is_empty() {
+            return T::ZERO;
+        }
+
+        self.ranges
+            .iter()
+            .fold(T::ONE, |acc, r| acc * (r.end - r.start))
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        self.ranges.iter().zip(point).all(|(r, p)| r.contains(p))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let mut ranges = self.ranges.clone();
+
+        for (r, o) in ranges.iter_mut().zip(other.ranges.iter()) {
+            *r = r.start.max(o.start)..r.end.min(o.end);
+        }
+
+        let res = RangeBox { ranges };
+        (!res.is_empty()).then_some(res)
+    }
+
+    /// Cuts the box along `axis`, values below `at` and values from `at` upwards.
+    pub fn split_at(&self, axis: usize, at: T) -> (Option<Self>, Option<Self>) {
+        let range = &self.ranges[axis];
+        let mid = at.clamp(range.start, range.end);
+
+        let mut below = self.clone();
+        let mut above = self.clone();
+        below.ranges[axis] = range.start..mid;
+        above.ranges[axis] = mid..range.end;
+
+        (
+            (!below.is_empty()).then_some(below),
+            (!above.is_empty()).then_some(above),
+        )
+    }
+
+    /// Parts of the box outside `other`, at most two slabs per axis, pairwise disjoint.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.intersection(other).is_none() {
+            return vec![self.clone()];
+        }
+
+        let mut pieces = vec![];
+        let mut rest = self.clone();
+
+        for (axis, range) in other.ranges.iter().enumerate() {
+            // the boxes overlap, so the middle slab is never empty
+            let (below, mid) = rest.split_at(axis, range.start);
+            let (mid, above) = mid.unwrap().split_at(axis, range.end);
+
+            pieces.extend(below);
+            pieces.extend(above);
+            rest = mid.unwrap();
+        }
+
+        pieces
+    }
+}
+
+/// Volume of a union of boxes with some carved out again, kept as signed
+/// inclusion–exclusion terms so no box ever has to be split.
+#[derive(Debug, Clone)]
+pub struct BoxUnion<T, const N: usize> {
+    /// `(box, added)`, removed terms cancel out double counted overlaps.
+    terms: Vec<(RangeBox<T, N>, bool)>,
+}
+
+impl<T, const N: usize> Default for BoxUnion<T, N> {
+    fn default() -> Self {
+        BoxUnion { terms: vec![] }
+    }
+}
+
+impl<T: Coord, const N: usize> FromIterator<RangeBox<T, N>> for BoxUnion<T, N> {
+    fn from_iter<I: IntoIterator<Item = RangeBox<T, N>>>(iter: I) -> Self {
+        let mut union = BoxUnion::default();
+
+        for b in iter {
+            union.add(&b);
+        }
+
+        union
+    }
+}
+
+impl<T: Coord, const N: usize> BoxUnion<T, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, b: &RangeBox<T, N>) {
+        self.remove(b);
+
+        if !b.is_empty() {
+            self.terms.push((b.clone(), true));
+        }
+    }
+
+    pub fn remove(&mut self, b: &RangeBox<T, N>) {
+        let overlaps = self
+            .terms
+            .iter()
+            .filter_map(|(t, added)| t.intersection(b).map(|i| (i, !added)))
+            .collect::<Vec<_>>();
+
+        self.terms.extend(overlaps);
+    }
+
+    /// Number of inclusion–exclusion terms, a rough measure of the work per update.
+    pub fn terms(&self) -> usize {
+        self.terms.len()
+    }
+
+    pub fn contains(&self, point: &[T; N]) -> bool {
+        let (added, removed) = self.terms.iter().filter(|(t, _)| t.contains(point)).fold(
+            (0usize, 0usize),
+            |(a, r), (_, added)| {
+                if *added {
+                    (a + 1, r)
+                } else {
+                    (a, r + 1)
+                }
+            },
+        );
+
+        added > removed
+    }
+
+    pub fn volume(&self) -> T {
+        // positive and negative parts kept apart so unsigned coordinates work too
+        let (added, removed) = self
+            .terms
+            .iter()
+            .fold((T::ZERO, T::ZERO), |(a, r), (t, added)| {
+                if *added {
+                    (a + t.volume(), r)
+                } else {
+                    (a, r + t.volume())
+                }
+            });
+
+        added - removed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cube(a: [i64; 3], b: [i64; 3]) -> RangeBox<i64, 3> {
+        RangeBox::new([a[0]..b[0], a[1]..b[1], a[2]..b[2]])
+    }
+
+    #[test]
+    fn box_check() {
+        let a = cube([0, 0, 0], [3, 3, 3]);
+        let b = cube([2, 2, 2], [5, 5, 5]);
+
+        assert_eq!(27, a.volume());
+        assert_eq!(Some(cube([2, 2, 2], [3, 3, 3])), a.intersection(&b));
+        assert_eq!(None, a.intersection(&cube([3, 0, 0], [4, 1, 1])));
+        assert!(a.contains(&[2, 0, 1]));
+        assert!(!a.contains(&[3, 0, 1]));
+
+        let (below, above) = a.split_at(1, 1);
+        assert_eq!(Some(cube([0, 0, 0], [3, 1, 3])), below);
+        assert_eq!(Some(cube([0, 1, 0], [3, 3, 3])), above);
+        assert_eq!((None, Some(a.clone())), a.split_at(0, -4));
+    }
+
+    #[test]
+    fn difference_check() {
+        let a = cube([0, 0, 0], [4, 4, 4]);
+
+        for b in [
+            cube([1, 1, 1], [3, 3, 3]),
+            cube([2, -1, 2], [6, 2, 6]),
+            cube([-1, -1, -1], [5, 5, 5]),
+            cube([4, 0, 0], [5, 4, 4]),
+        ] {
+            let pieces = a.difference(&b);
+            let expected = a.volume() - a.intersection(&b).map_or(0, |i| i.volume());
+
+            assert_eq!(expected, pieces.iter().map(|p| p.volume()).sum::<i64>());
+            assert!(pieces.iter().all(|p| p.intersection(&b).is_none()));
+            assert!(pieces
+                .iter()
+                .enumerate()
+                .all(|(i, p)| pieces[i + 1..].iter().all(|q| p.intersection(q).is_none())));
+        }
+    }
+
+    #[test]
+    fn union_check() {
+        let boxes = [
+            (cube([0, 0, 0], [4, 4, 4]), true),
+            (cube([2, 2, 2], [6, 5, 4]), true),
+            (cube([1, 1, 1], [3, 3, 3]), false),
+            (cube([-2, 0, 0], [1, 7, 2]), true),
+            (cube([0, 0, 0], [1, 1, 1]), false),
+            (cube([3, 3, 3], [4, 4, 4]), true),
+        ];
+
+        let mut union = BoxUnion::new();
+        let mut points = std::collections::HashSet::new();
+
+        for (b, on) in boxes.iter() {
+            if *on {
+                union.add(b);
+            } else {
+                union.remove(b);
+            }
+
+            for x in -3..8 {
+                for y in -3..8 {
+                    for z in -3..8 {
+                        if b.contains(&[x, y, z]) {
+                            if *on {
+                                points.insert([x, y, z]);
+                            } else {
+                                points.remove(&[x, y, z]);
+                            }
+                        }
+                    }
+                }
+            }
+
+            assert_eq!(points.len() as i64, union.volume());
+            assert!(points.iter().all(|p| union.contains(p)));
+        }
+    }
+
+    #[test]
+    fn unsigned_union_check() {
+        let union = [0..4u64, 2..6, 5..9]
+            .into_iter()
+            .map(|r| RangeBox::new([r.clone(), r]))
+            .collect::<BoxUnion<_, 2>>();
+
+        assert_eq!(16 + 16 + 16 - 4 - 1, union.volume());
+    }
+}
@@ -0,0 +1,299 @@
+use std::ops::{Range, RangeInclusive};
+
+use crate::Coord;
+
+/// Set of values stored as sorted, disjoint and non-touching half-open ranges.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RangeSet<T> {
+    ranges: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        RangeSet { ranges: vec![] }
+    }
+}
+
+impl<T: Coord> From<Range<T>> for RangeSet<T> {
+    fn from(range: Range<T>) -> Self {
+        RangeSet::from_iter([range])
+    }
+}
+
+impl<T: Coord> TryFrom<RangeInclusive<T>> for RangeSet<T> {
+    type Error = String;
+
+    fn try_from(range: RangeInclusive<T>) -> Result<Self, Self::Error> {
+        RangeSet::try_from_inclusive([range])
+    }
+}
+
+impl<T: Coord> FromIterator<Range<T>> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut ranges = iter
+            .into_iter()
+            .filter(|r| r.start < r.end)
+            .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|r| r.start);
+
+        let mut merged: Vec<Range<T>> = Vec::with_capacity(ranges.len());
+
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+
+        RangeSet { ranges: merged }
+    }
+}
+
+impl<T: Coord> RangeSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set of inclusive ranges. Ranges are kept half-open, so one ending at
+    /// the largest value of its type can't be stored and is an error.
+    pub fn try_from_inclusive<I: IntoIterator<Item = RangeInclusive<T>>>(
+        iter: I,
+    ) -> Result<Self, String> {
+        iter.into_iter()
+            .filter(|r| !r.is_empty())
+            .map(|r| match r.end().checked_add(T::ONE) {
+                Some(end) => Ok(*r.start()..end),
+                None => Err(format!("Range ends at the maximum: {:?}", r)),
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |acc, r| acc + (r.end - r.start))
+    }
+
+    pub fn ranges(&self) -> &[Range<T>] {
+        &self.ranges
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| r.end - T::ONE)
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        *self = self.union(&RangeSet::from(range));
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.ranges.iter().chain(&other.ranges).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while let (Some(a), Some(b)) = (self.ranges.get(i), other.ranges.get(j)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+
+            if start < end {
+                res.push(start..end);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        RangeSet { ranges: res }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = vec![];
+        let mut j = 0;
+
+        for range in self.ranges.iter() {
+            let mut start = range.start;
+
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+
+            // `other` ranges are never consumed here, they may reach into the next range
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                if start < other.ranges[k].start {
+                    res.push(start..other.ranges[k].start);
+                }
+                start = start.max(other.ranges[k].end);
+                k += 1;
+            }
+
+            if start < range.end {
+                res.push(start..range.end);
+            }
+        }
+
+        RangeSet { ranges: res }
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    /// Values below `at` and values from `at` upwards.
+    pub fn split_at(&self, at: T) -> (Self, Self) {
+        let mut below = vec![];
+        let mut above = vec![];
+
+        for range in self.ranges.iter() {
+            if range.end <= at {
+                below.push(range.clone());
+            } else if range.start >= at {
+                above.push(range.clone());
+            } else {
+                below.push(range.start..at);
+                above.push(at..range.end);
+            }
+        }
+
+        (RangeSet { ranges: below }, RangeSet { ranges: above })
+    }
+
+    /// Moves every value up by `plus` and down by `minus`,
+    /// so unsigned coordinates can shift both ways.
+    pub fn shift(&self, plus: T, minus: T) -> Self {
+        RangeSet {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| shift_value(r.start, plus, minus)..shift_value(r.end, plus, minus))
+                .collect(),
+        }
+    }
+}
+
+/// Subtracts first when possible, unsigned values near either end never overflow midway.
+fn shift_value<T: Coord>(value: T, plus: T, minus: T) -> T {
+    if value >= minus {
+        value - minus + plus
+    } else {
+        value + plus - minus
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[(i32, i32)]) -> RangeSet<i32> {
+        ranges.iter().map(|&(a, b)| a..b).collect()
+    }
+
+    fn brute(set: &RangeSet<i32>) -> Vec<i32> {
+        (-20..40).filter(|&v| set.contains(v)).collect()
+    }
+
+    #[test]
+    fn merge_check() {
+        let s = set(&[(5, 8), (1, 3), (3, 4), (7, 10), (12, 12)]);
+
+        assert_eq!(&[1..4, 5..10], s.ranges());
+        assert_eq!(8, s.len());
+        assert_eq!(Some(1), s.min());
+        assert_eq!(Some(9), s.max());
+        assert!(!s.contains(4));
+        assert!(s.contains(9));
+    }
+
+    #[test]
+    fn inclusive_check() {
+        let s = RangeSet::try_from_inclusive([3..=5u64, 10..=14, 16..=20, 12..=18]).unwrap();
+
+        assert_eq!(&[3..6, 10..21], s.ranges());
+        assert_eq!(14, s.len());
+    }
+
+    #[test]
+    fn inclusive_bound_check() {
+        let s = RangeSet::try_from_inclusive([250u8..=254, 0..=3]).unwrap();
+
+        assert_eq!(&[0..4, 250..255], s.ranges());
+        assert_eq!(Some(254), s.max());
+    }
+
+    #[test]
+    fn inclusive_max_check() {
+        assert_eq!(
+            Err("Range ends at the maximum: 250..=255".to_owned()),
+            RangeSet::try_from(250u8..=255)
+        );
+        assert!(RangeSet::try_from_inclusive([0..=3, 9u8..=255]).is_err());
+    }
+
+    #[test]
+    fn set_operations_check() {
+        let samples = [
+            set(&[]),
+            set(&[(0, 5)]),
+            set(&[(-10, -2), (3, 7), (9, 30)]),
+            set(&[(-3, 1), (2, 4), (6, 12), (20, 21)]),
+            set(&[(4, 5), (7, 9), (15, 25)]),
+        ];
+
+        for a in samples.iter() {
+            for b in samples.iter() {
+                let (va, vb) = (brute(a), brute(b));
+
+                let union = (-20..40).filter(|v| va.contains(v) || vb.contains(v));
+                let inter = (-20..40).filter(|v| va.contains(v) && vb.contains(v));
+                let diff = (-20..40).filter(|v| va.contains(v) && !vb.contains(v));
+
+                assert_eq!(union.collect::<Vec<_>>(), brute(&a.union(b)));
+                assert_eq!(inter.collect::<Vec<_>>(), brute(&a.intersection(b)));
+                assert_eq!(diff.collect::<Vec<_>>(), brute(&a.difference(b)));
+                assert_eq!(a.union(b), a.union(b).ranges().iter().cloned().collect());
+                assert_eq!(va.iter().all(|v| vb.contains(v)), b.is_superset(a));
+            }
+        }
+    }
+
+    #[test]
+    fn split_check() {
+        let s = set(&[(0, 5), (8, 10)]);
+
+        assert_eq!((set(&[(0, 3)]), set(&[(3, 5), (8, 10)])), s.split_at(3));
+        assert_eq!((set(&[(0, 5)]), set(&[(8, 10)])), s.split_at(6));
+        assert_eq!((set(&[]), s.clone()), s.split_at(-1));
+    }
+
+    #[test]
+    fn shift_check() {
+        let s = RangeSet::from(5u32..8);
+
+        assert_eq!(RangeSet::from(2u32..5), s.shift(0, 3));
+        assert_eq!(RangeSet::from(9u32..12), s.shift(4, 0));
+    }
+}